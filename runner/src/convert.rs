@@ -0,0 +1,127 @@
+use robotics_lib::event::events as lib_events;
+use robotics_lib::world::environmental_conditions as lib_environment;
+use robotics_lib::world::tile as lib_tile;
+
+use visualizer::world::{Content, EnvironmentalConditions, Event, Tile, TileType, WeatherType};
+
+// robotics_lib's types turned into the visualizer's copies in world.rs
+
+pub fn tile_type(tile_type: &lib_tile::TileType) -> TileType {
+    match tile_type {
+        lib_tile::TileType::DeepWater => TileType::DeepWater,
+        lib_tile::TileType::ShallowWater => TileType::ShallowWater,
+        lib_tile::TileType::Sand => TileType::Sand,
+        lib_tile::TileType::Grass => TileType::Grass,
+        lib_tile::TileType::Street => TileType::Street,
+        lib_tile::TileType::Hill => TileType::Hill,
+        lib_tile::TileType::Mountain => TileType::Mountain,
+        lib_tile::TileType::Snow => TileType::Snow,
+        lib_tile::TileType::Lava => TileType::Lava,
+        lib_tile::TileType::Teleport(used) => TileType::Teleport(*used),
+        lib_tile::TileType::Wall => TileType::Wall,
+    }
+}
+
+pub fn content(content: &lib_tile::Content) -> Content {
+    match content {
+        lib_tile::Content::Rock(amount) => Content::Rock(*amount),
+        lib_tile::Content::Tree(amount) => Content::Tree(*amount),
+        lib_tile::Content::Garbage(amount) => Content::Garbage(*amount),
+        lib_tile::Content::Fire => Content::Fire,
+        lib_tile::Content::Coin(amount) => Content::Coin(*amount),
+        lib_tile::Content::Bin(range) => Content::Bin(range.clone()),
+        lib_tile::Content::Crate(range) => Content::Crate(range.clone()),
+        lib_tile::Content::Bank(range) => Content::Bank(range.clone()),
+        lib_tile::Content::Water(amount) => Content::Water(*amount),
+        lib_tile::Content::Market(amount) => Content::Market(*amount),
+        lib_tile::Content::Fish(amount) => Content::Fish(*amount),
+        lib_tile::Content::Building => Content::Building,
+        lib_tile::Content::Bush(amount) => Content::Bush(*amount),
+        lib_tile::Content::JollyBlock(amount) => Content::JollyBlock(*amount),
+        lib_tile::Content::Scarecrow => Content::Scarecrow,
+        lib_tile::Content::None => Content::None,
+    }
+}
+
+pub fn tile(tile: &lib_tile::Tile) -> Tile {
+    Tile {
+        tile_type: tile_type(&tile.tile_type),
+        content: content(&tile.content),
+        elevation: tile.elevation,
+    }
+}
+
+pub fn map(map: &[Vec<Option<lib_tile::Tile>>]) -> Vec<Vec<Option<Tile>>> {
+    map.iter()
+        .map(|row| row.iter().map(|cell| cell.as_ref().map(tile)).collect())
+        .collect()
+}
+
+pub fn weather(weather: &lib_environment::WeatherType) -> WeatherType {
+    match weather {
+        lib_environment::WeatherType::Sunny => WeatherType::Sunny,
+        lib_environment::WeatherType::Rainy => WeatherType::Rainy,
+        lib_environment::WeatherType::Foggy => WeatherType::Foggy,
+        lib_environment::WeatherType::TropicalMonsoon => WeatherType::TropicalMonsoon,
+        lib_environment::WeatherType::TrentinoSnow => WeatherType::TrentinoSnow,
+    }
+}
+
+pub fn environment(
+    conditions: &lib_environment::EnvironmentalConditions,
+) -> EnvironmentalConditions {
+    EnvironmentalConditions::new(
+        conditions.get_time_of_day_string(),
+        weather(&conditions.get_weather_condition()),
+    )
+}
+
+pub fn event(event: &lib_events::Event) -> Event {
+    match event {
+        lib_events::Event::Ready => Event::Ready,
+        lib_events::Event::Terminated => Event::Terminated,
+        lib_events::Event::TimeChanged(conditions) => Event::TimeChanged(environment(conditions)),
+        lib_events::Event::DayChanged(conditions) => Event::DayChanged(environment(conditions)),
+        lib_events::Event::EnergyRecharged(amount) => Event::EnergyRecharged(*amount),
+        lib_events::Event::EnergyConsumed(amount) => Event::EnergyConsumed(*amount),
+        lib_events::Event::Moved(moved_to, position) => Event::Moved(tile(moved_to), *position),
+        lib_events::Event::TileContentUpdated(updated, position) => {
+            Event::TileContentUpdated(tile(updated), *position)
+        }
+        lib_events::Event::AddedToBackpack(added, amount) => {
+            Event::AddedToBackpack(content(added), *amount)
+        }
+        lib_events::Event::RemovedFromBackpack(removed, amount) => {
+            Event::RemovedFromBackpack(content(removed), *amount)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn properties_match_robotics_lib() {
+        let all = [
+            lib_tile::TileType::DeepWater,
+            lib_tile::TileType::ShallowWater,
+            lib_tile::TileType::Sand,
+            lib_tile::TileType::Grass,
+            lib_tile::TileType::Street,
+            lib_tile::TileType::Hill,
+            lib_tile::TileType::Mountain,
+            lib_tile::TileType::Snow,
+            lib_tile::TileType::Lava,
+            lib_tile::TileType::Teleport(false),
+            lib_tile::TileType::Teleport(true),
+            lib_tile::TileType::Wall,
+        ];
+        for lib_type in all {
+            let expected = lib_type.properties();
+            let properties = tile_type(&lib_type).properties();
+            assert_eq!(properties.walk(), expected.walk(), "{:?}", lib_type);
+            assert_eq!(properties.cost(), expected.cost(), "{:?}", lib_type);
+        }
+    }
+}
@@ -0,0 +1,24 @@
+/*
+visualizer
+advanced programming course 23-24
+university of trento
+
+https://github.com/davidepaci
+*/
+
+// runs a robot in the visualizer, the gui itself is the crate one directory up
+mod convert;
+mod robots;
+mod runner;
+mod wrapper;
+
+use crate::robots::RobotRegistry;
+
+fn main() {
+    let args = visualizer::parse_args();
+    if args.list_bots {
+        print!("{}", RobotRegistry::with_default_robots().describe());
+        return;
+    }
+    visualizer::run(args, Some(runner::runner_source));
+}
@@ -0,0 +1,161 @@
+use std::collections::HashMap;
+use std::fmt::Write;
+use std::str::FromStr;
+
+use robotics_lib::runner::Runnable;
+
+use oxagaudiotool::sound_config::OxAgSoundConfig;
+
+use my_robot::MyRobot;
+use saver_bot::SaverBot;
+
+// a parameter a robot accepts with --param name=value
+pub struct ParamSpec {
+    pub name: &'static str,
+    pub description: &'static str,
+    pub required: bool,
+}
+
+// everything needed to list, select and build a robot
+pub struct RobotFactory {
+    pub name: &'static str,
+    pub description: &'static str,
+    pub params: &'static [ParamSpec],
+    pub build: fn(&BotParams) -> Result<Box<dyn Runnable>, String>,
+}
+
+impl RobotFactory {
+    // check the params against the schema, then build the robot
+    pub fn create(&self, params: &BotParams) -> Result<Box<dyn Runnable>, String> {
+        for name in params.0.keys() {
+            if !self.params.iter().any(|spec| spec.name == name) {
+                return Err(format!(
+                    "robot '{}' has no parameter '{}' (valid: {})",
+                    self.name,
+                    name,
+                    self.param_names()
+                ));
+            }
+        }
+        for spec in self.params.iter().filter(|spec| spec.required) {
+            if !params.0.contains_key(spec.name) {
+                return Err(format!(
+                    "robot '{}' needs parameter '{}': {}",
+                    self.name, spec.name, spec.description
+                ));
+            }
+        }
+        (self.build)(params)
+    }
+
+    fn param_names(&self) -> String {
+        if self.params.is_empty() {
+            return String::from("none");
+        }
+        self.params
+            .iter()
+            .map(|spec| spec.name)
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+}
+
+// raw --param values, parsed by each factory
+#[derive(Default, Clone, Debug)]
+pub struct BotParams(pub HashMap<String, String>);
+
+impl BotParams {
+    pub fn get<T: FromStr>(&self, name: &str) -> Result<Option<T>, String> {
+        match self.0.get(name) {
+            Some(value) => value
+                .parse::<T>()
+                .map(Some)
+                .map_err(|_| format!("invalid value '{}' for parameter '{}'", value, name)),
+            None => Ok(None),
+        }
+    }
+}
+
+#[derive(Default)]
+pub struct RobotRegistry {
+    factories: Vec<RobotFactory>,
+}
+
+impl RobotRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // 🤖 register new robots here 🤖
+    pub fn with_default_robots() -> Self {
+        let mut registry = Self::new();
+        registry.register(RobotFactory {
+            name: "saver",
+            description: "SaverBot, collects coins and deposits them in banks",
+            params: &[ParamSpec {
+                name: "coins",
+                description: "amount of coins to collect",
+                required: false,
+            }],
+            build: build_saver_bot,
+        });
+        registry.register(RobotFactory {
+            name: "my_robot",
+            description: "MyRobot from project_feb",
+            params: &[],
+            build: build_my_robot,
+        });
+        registry
+    }
+
+    pub fn register(&mut self, factory: RobotFactory) {
+        // later registrations replace earlier ones with the same name
+        self.factories.retain(|f| f.name != factory.name);
+        self.factories.push(factory);
+    }
+
+    pub fn get(&self, name: &str) -> Result<&RobotFactory, String> {
+        self.factories
+            .iter()
+            .find(|factory| factory.name == name)
+            .ok_or_else(|| format!("unknown robot '{}' (valid: {})", name, self.names()))
+    }
+
+    pub fn names(&self) -> String {
+        self.factories
+            .iter()
+            .map(|factory| factory.name)
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+
+    // text printed by --list-bots
+    pub fn describe(&self) -> String {
+        let mut out = String::new();
+        for factory in self.factories.iter() {
+            let _ = writeln!(out, "{:<12} {}", factory.name, factory.description);
+            for spec in factory.params.iter() {
+                let _ = writeln!(
+                    out,
+                    "    {:<8} {}{}",
+                    spec.name,
+                    spec.description,
+                    if spec.required { " (required)" } else { "" }
+                );
+            }
+        }
+        out
+    }
+}
+
+fn build_saver_bot(params: &BotParams) -> Result<Box<dyn Runnable>, String> {
+    let mut saver_bot = SaverBot::new(params.get::<usize>("coins")?);
+    // Play background music
+    let background_music = OxAgSoundConfig::new_looped_with_volume("assets/default/music.ogg", 2.0);
+    let _ = saver_bot.audio.play_audio(&background_music);
+    Ok(Box::new(saver_bot))
+}
+
+fn build_my_robot(_params: &BotParams) -> Result<Box<dyn Runnable>, String> {
+    Ok(Box::new(MyRobot::new()))
+}
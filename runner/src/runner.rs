@@ -0,0 +1,93 @@
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::rc::Rc;
+use std::sync::Arc;
+
+use robotics_lib::runner::Runner;
+use robotics_lib::world::environmental_conditions::EnvironmentalConditions;
+use robotics_lib::world::tile as lib_tile;
+use robotics_lib::world::world_generator::Generator;
+use worldgen_unwrap::public::WorldgeneratorUnwrap;
+
+use visualizer::cli::Args;
+use visualizer::simulation::{TickOutcome, TickSource};
+use visualizer::snapshot::SnapshotSender;
+use visualizer::world::{Event, Tile};
+
+use crate::convert;
+
+use crate::robots::{BotParams, RobotRegistry};
+// 🌯 runner wrapper 🌯
+use crate::wrapper::VisualizerRobotWrapper;
+
+// the robot and world given on the command line, ticked by the visualizer's simulation thread
+pub fn runner_source(
+    args: &Args,
+    snapshots: SnapshotSender,
+) -> Result<(TickSource, Vec<Vec<Tile>>), String> {
+    let world_path = args.world.clone().ok_or("no world file given")?;
+
+    // Create robot, the wrapper builds a snapshot of every tick and it is sent
+    // through the channel once the tick is over
+    let registry = RobotRegistry::with_default_robots();
+    let factory = registry.get(&args.bot)?;
+    let params = BotParams(args.params.iter().cloned().collect());
+    let pending = Rc::new(RefCell::new(None));
+    let robot = VisualizerRobotWrapper::new(factory.create(&params)?, pending.clone());
+
+    // Create world
+    let mut worldgen = KeepGrid {
+        generator: WorldgeneratorUnwrap::init(false, Some(world_path)),
+        grid: Vec::new(),
+    };
+
+    let mut runner = Runner::new(Box::new(robot), &mut worldgen)
+        .map_err(|err| format!("could not create the runner: {:?}", err))?;
+    let ground_truth = worldgen.grid;
+    let source: TickSource = Box::new(move || {
+        let result = runner.game_tick();
+        // sent once the whole tick ran, so it has the events of the time update as well
+        let mut terminated = false;
+        if let Some(snapshot) = pending.borrow_mut().take() {
+            terminated = snapshot.events.contains(&Event::Terminated);
+            // nobody listening anymore, the gui was closed
+            let _ = snapshots.send(Arc::new(snapshot));
+        }
+        if let Err(err) = result {
+            return TickOutcome::Failed(format!("tick failed: {:?}", err));
+        }
+        if terminated {
+            TickOutcome::Finished
+        } else {
+            TickOutcome::Ticked
+        }
+    });
+    Ok((source, ground_truth))
+}
+
+// passes the world on to the runner and keeps a copy of it as the visualizer's
+// initial world, so the file is only generated once
+struct KeepGrid<G> {
+    generator: G,
+    grid: Vec<Vec<Tile>>,
+}
+
+impl<G: Generator> Generator for KeepGrid<G> {
+    fn gen(
+        &mut self,
+    ) -> (
+        Vec<Vec<lib_tile::Tile>>,
+        (usize, usize),
+        EnvironmentalConditions,
+        f32,
+        Option<HashMap<lib_tile::Content, f32>>,
+    ) {
+        let world = self.generator.gen();
+        self.grid = world
+            .0
+            .iter()
+            .map(|row| row.iter().map(convert::tile).collect())
+            .collect();
+        world
+    }
+}
@@ -0,0 +1,107 @@
+use crate::convert;
+use robotics_lib::energy::{Energy, MAX_ENERGY_LEVEL};
+use robotics_lib::event::events::Event as LibEvent;
+use robotics_lib::interface::{look_at_sky, robot_map, where_am_i};
+use robotics_lib::runner::backpack::BackPack;
+use robotics_lib::runner::Runnable;
+use robotics_lib::world::coordinates::Coordinate;
+use robotics_lib::world::World;
+use std::cell::RefCell;
+use std::rc::Rc;
+use visualizer::snapshot::{map_changes, same_shape, BackpackSnapshot, TickSnapshot};
+use visualizer::world::{Event, Tile};
+
+pub struct VisualizerRobotWrapper {
+    runnable: Box<dyn Runnable>,
+    // the tick just processed, the runner still sends events for it after process_tick
+    // returns. taken and sent to the gui by the runner's tick source
+    snapshot: Rc<RefCell<Option<TickSnapshot>>>,
+    tick: u64,
+    pending_events: Vec<Event>,
+    // map of the last snapshot, to send only what changed to the gui
+    previous_map: Vec<Vec<Option<Tile>>>,
+}
+
+impl VisualizerRobotWrapper {
+    pub fn new(runnable: Box<dyn Runnable>, snapshot: Rc<RefCell<Option<TickSnapshot>>>) -> Self {
+        Self {
+            runnable,
+            snapshot,
+            tick: 0,
+            pending_events: Vec::new(),
+            previous_map: Vec::new(),
+        }
+    }
+}
+
+impl Runnable for VisualizerRobotWrapper {
+    fn process_tick(&mut self, world: &mut World) {
+        self.runnable.process_tick(world);
+        self.tick += 1;
+        // gather the whole tick at once so the gui never mixes two ticks
+        let backpack = self.runnable.get_backpack();
+        let map = convert::map(&robot_map(world).unwrap_or_default());
+        let changes =
+            same_shape(&self.previous_map, &map).then(|| map_changes(&self.previous_map, &map));
+        self.previous_map = map.clone();
+        let snapshot = TickSnapshot {
+            tick: self.tick,
+            map,
+            changes,
+            robot_position: where_am_i(self, world).1,
+            environment: convert::environment(&look_at_sky(world)),
+            energy: self.runnable.get_energy().get_energy_level(),
+            max_energy: MAX_ENERGY_LEVEL,
+            backpack: BackpackSnapshot {
+                size: backpack.get_size(),
+                contents: backpack
+                    .get_contents()
+                    .iter()
+                    .map(|(content, amount)| (convert::content(content), *amount))
+                    .collect(),
+            },
+            events: std::mem::take(&mut self.pending_events),
+        };
+        *self.snapshot.borrow_mut() = Some(snapshot);
+    }
+
+    fn handle_event(&mut self, event: LibEvent) {
+        let converted = convert::event(&event);
+        match self.snapshot.borrow_mut().as_mut() {
+            // time and weather change after the robot's turn, still in the same tick
+            Some(snapshot) => {
+                if let Event::TimeChanged(environment) | Event::DayChanged(environment) = &converted
+                {
+                    snapshot.environment = environment.clone();
+                }
+                snapshot.events.push(converted);
+            }
+            None => self.pending_events.push(converted),
+        }
+        self.runnable.handle_event(event)
+    }
+
+    fn get_energy(&self) -> &Energy {
+        self.runnable.get_energy()
+    }
+
+    fn get_energy_mut(&mut self) -> &mut Energy {
+        self.runnable.get_energy_mut()
+    }
+
+    fn get_coordinate(&self) -> &Coordinate {
+        self.runnable.get_coordinate()
+    }
+
+    fn get_coordinate_mut(&mut self) -> &mut Coordinate {
+        self.runnable.get_coordinate_mut()
+    }
+
+    fn get_backpack(&self) -> &BackPack {
+        self.runnable.get_backpack()
+    }
+
+    fn get_backpack_mut(&mut self) -> &mut BackPack {
+        self.runnable.get_backpack_mut()
+    }
+}
@@ -0,0 +1,233 @@
+use std::collections::{BTreeMap, VecDeque};
+
+use bevy::prelude::Resource;
+
+use crate::snapshot::TickSnapshot;
+use crate::tiles::content_name;
+use crate::world::{Content, Event, Tile};
+
+// ticks shown at once, zoomed through with [ and ], 0 shows everything kept
+const TIME_WINDOWS: [u64; 8] = [25, 50, 100, 250, 500, 1000, 5000, 0];
+const DEFAULT_WINDOW: usize = 3;
+
+// one chart of the panel
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum ChartKind {
+    Energy,
+    Backpack,
+    Coins,
+    Discovered,
+}
+
+impl ChartKind {
+    pub const ALL: [ChartKind; 4] = [
+        ChartKind::Energy,
+        ChartKind::Backpack,
+        ChartKind::Coins,
+        ChartKind::Discovered,
+    ];
+}
+
+// values plotted for one tick
+#[derive(Clone, Debug)]
+pub struct ChartPoint {
+    pub(crate) tick: u64,
+    pub(crate) energy: usize,
+    pub(crate) max_energy: usize,
+    pub(crate) backpack: BTreeMap<&'static str, usize>,
+    pub(crate) coins_collected: usize,
+    pub(crate) coins_deposited: usize,
+    pub(crate) discovered: usize,
+}
+
+// every tick received, aggregated for the charts, oldest first
+#[derive(Resource)]
+pub struct ChartData {
+    points: VecDeque<ChartPoint>,
+    capacity: usize,
+    // everything that was ever in the backpack, in order of appearance
+    pub(crate) items: Vec<&'static str>,
+    window: usize,
+    pub(crate) visible: bool,
+}
+
+impl ChartData {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            points: VecDeque::new(),
+            capacity: capacity.max(1),
+            items: Vec::new(),
+            window: DEFAULT_WINDOW,
+            visible: false,
+        }
+    }
+
+    pub fn record(&mut self, snapshot: &TickSnapshot) {
+        // ticks arrive in order, anything older was already recorded
+        if self
+            .points
+            .back()
+            .is_some_and(|last| snapshot.tick <= last.tick)
+        {
+            return;
+        }
+        let (mut coins_collected, mut coins_deposited) = self
+            .points
+            .back()
+            .map_or((0, 0), |last| (last.coins_collected, last.coins_deposited));
+        // coins also leave the backpack when they are sold or dropped, they only count
+        // as deposited when a bank changed in the same tick
+        let bank_changed = snapshot.changes.as_ref().is_some_and(|changes| {
+            changes.iter().any(|(_, _, tile)| {
+                matches!(
+                    tile,
+                    Some(Tile {
+                        content: Content::Bank(_),
+                        ..
+                    })
+                )
+            })
+        });
+        for event in snapshot.events.iter() {
+            match event {
+                Event::AddedToBackpack(Content::Coin(_), amount) => coins_collected += amount,
+                Event::RemovedFromBackpack(Content::Coin(_), amount) if bank_changed => {
+                    coins_deposited += amount
+                }
+                _ => {}
+            }
+        }
+        let mut backpack = BTreeMap::new();
+        for (content, amount) in snapshot.backpack.contents.iter() {
+            let name = content_name(content);
+            *backpack.entry(name).or_insert(0) += amount;
+            if *amount > 0 && !self.items.contains(&name) {
+                self.items.push(name);
+            }
+        }
+        self.points.push_back(ChartPoint {
+            tick: snapshot.tick,
+            energy: snapshot.energy,
+            max_energy: snapshot.max_energy,
+            backpack,
+            coins_collected,
+            coins_deposited,
+            discovered: snapshot.map.iter().flatten().flatten().count(),
+        });
+        while self.points.len() > self.capacity {
+            self.points.pop_front();
+        }
+    }
+
+    // points in the time window that ends at `tick`
+    pub fn window_ending_at(&self, tick: u64) -> Vec<&ChartPoint> {
+        let end = self.points.partition_point(|point| point.tick <= tick);
+        let start = match self.window_ticks() {
+            0 => 0,
+            ticks => self
+                .points
+                .partition_point(|point| point.tick + ticks <= tick),
+        };
+        self.points.range(start..end).collect()
+    }
+
+    // ticks shown at once, 0 for all of them
+    pub fn window_ticks(&self) -> u64 {
+        TIME_WINDOWS[self.window]
+    }
+
+    // positive shows more ticks, negative fewer
+    pub fn zoom(&mut self, steps: i32) {
+        self.window = (self.window as i32 + steps).clamp(0, TIME_WINDOWS.len() as i32 - 1) as usize;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::snapshot::tests::{map, snapshot};
+    use crate::world::TileType;
+
+    fn charts(ticks: u64, capacity: usize) -> ChartData {
+        let mut charts = ChartData::new(capacity);
+        for tick in 1..=ticks {
+            let mut snapshot = snapshot(tick, map(2, 2, 0));
+            snapshot.energy = tick as usize;
+            charts.record(&snapshot);
+        }
+        charts
+    }
+
+    fn ticks(points: &[&ChartPoint]) -> (u64, u64, usize) {
+        (points[0].tick, points[points.len() - 1].tick, points.len())
+    }
+
+    #[test]
+    fn window_ends_at_the_tick() {
+        let charts = charts(300, 1000);
+        assert_eq!(charts.window_ticks(), 250);
+        assert_eq!(ticks(&charts.window_ending_at(300)), (51, 300, 250));
+        assert_eq!(ticks(&charts.window_ending_at(120)), (1, 120, 120));
+        assert_eq!(ticks(&charts.window_ending_at(251)), (2, 251, 250));
+        assert!(charts.window_ending_at(0).is_empty());
+    }
+
+    #[test]
+    fn zoom_changes_the_window() {
+        let mut charts = charts(300, 1000);
+        charts.zoom(-10);
+        assert_eq!(charts.window_ticks(), 25);
+        assert_eq!(ticks(&charts.window_ending_at(300)), (276, 300, 25));
+        // the widest window shows everything kept
+        charts.zoom(10);
+        assert_eq!(charts.window_ticks(), 0);
+        assert_eq!(ticks(&charts.window_ending_at(300)), (1, 300, 300));
+    }
+
+    #[test]
+    fn only_capacity_points_are_kept() {
+        let mut charts = charts(300, 100);
+        charts.zoom(10);
+        assert_eq!(ticks(&charts.window_ending_at(300)), (201, 300, 100));
+    }
+
+    fn bank(filled: usize) -> Tile {
+        Tile {
+            tile_type: TileType::Grass,
+            content: Content::Bank(filled..10),
+            elevation: 0,
+        }
+    }
+
+    #[test]
+    fn coins_add_up() {
+        let mut charts = ChartData::new(100);
+        let mut first = snapshot(1, map(1, 1, 0));
+        first.events = vec![Event::AddedToBackpack(Content::Coin(0), 5)];
+        first.backpack.contents.insert(Content::Coin(0), 5);
+        charts.record(&first);
+        let mut second = snapshot(2, map(1, 1, 0));
+        second.events = vec![
+            Event::RemovedFromBackpack(Content::Coin(0), 3),
+            Event::AddedToBackpack(Content::Coin(0), 1),
+        ];
+        second.changes = Some(vec![(0, 0, Some(bank(3)))]);
+        second.backpack.contents.insert(Content::Coin(0), 3);
+        charts.record(&second);
+        // ticks already recorded are ignored
+        charts.record(&first);
+        // coins removed without a bank changing weren't deposited
+        let mut third = snapshot(3, map(1, 1, 0));
+        third.events = vec![Event::RemovedFromBackpack(Content::Coin(0), 2)];
+        third.backpack.contents.insert(Content::Coin(0), 1);
+        charts.record(&third);
+
+        let points = charts.window_ending_at(3);
+        assert_eq!(points.len(), 3);
+        assert_eq!(points[1].coins_collected, 6);
+        assert_eq!(points[1].coins_deposited, 3);
+        assert_eq!(points[1].backpack.get("coin"), Some(&3));
+        assert_eq!(points[2].coins_deposited, 3);
+        assert_eq!(charts.items, vec!["coin"]);
+    }
+}
@@ -0,0 +1,263 @@
+use std::path::PathBuf;
+
+use bevy::prelude::{KeyCode, Resource};
+use clap::{Parser, ValueEnum};
+
+/// Visualizer for a robotics_lib robot running in a generated world
+#[derive(Parser, Resource, Clone, Debug)]
+#[command(name = "visualizer", version, about)]
+pub struct Args {
+    /// World file to load with worldgen_unwrap
+    #[arg(short, long, value_name = "FILE", value_parser = parse_existing_file, required_unless_present_any = ["list_bots", "replay"])]
+    pub world: Option<PathBuf>,
+
+    /// Robot to run, see --list-bots
+    #[arg(short, long, value_name = "NAME", default_value = "saver")]
+    pub bot: String,
+
+    /// Robot parameter, can be repeated
+    #[arg(short, long = "param", value_name = "KEY=VALUE", value_parser = parse_param)]
+    pub params: Vec<(String, String)>,
+
+    /// Print the available robots and their parameters, then exit
+    #[arg(long)]
+    pub list_bots: bool,
+
+    /// Record every tick to a replay file
+    #[arg(long, value_name = "FILE")]
+    pub record: Option<PathBuf>,
+
+    /// Play back a replay file instead of running a robot
+    #[arg(long, value_name = "FILE", value_parser = parse_existing_file, conflicts_with_all = ["world", "bot", "params", "record"])]
+    pub replay: Option<PathBuf>,
+
+    /// Seconds between two ticks of the runner
+    #[arg(short, long, value_name = "SECONDS", default_value_t = 2.0, value_parser = parse_tick_interval)]
+    pub tick_interval: f32,
+
+    /// Window width in logical pixels
+    #[arg(long, value_name = "PIXELS", default_value_t = 1280.0, value_parser = parse_window_dimension)]
+    pub width: f32,
+
+    /// Window height in logical pixels
+    #[arg(long, value_name = "PIXELS", default_value_t = 720.0, value_parser = parse_window_dimension)]
+    pub height: f32,
+
+    /// Number of ticks kept for the timeline
+    #[arg(long, value_name = "TICKS", default_value_t = 100_000)]
+    pub history: usize,
+
+    /// Day/night lighting keyframes (JSON), see assets/lighting.json
+    #[arg(long, value_name = "FILE", value_parser = parse_existing_file)]
+    pub lighting: Option<PathBuf>,
+
+    /// Corner of the window the minimap is drawn in
+    #[arg(long, value_enum, value_name = "CORNER", default_value_t = MinimapCorner::TopLeft)]
+    pub minimap_corner: MinimapCorner,
+
+    /// Length in logical pixels of the minimap's longer side
+    #[arg(long, value_name = "PIXELS", default_value_t = 256, value_parser = clap::value_parser!(u32).range(64..=1024))]
+    pub minimap_size: u32,
+
+    /// HUD panels shown at start, the others can be opened with F1 to F4
+    #[arg(long, value_enum, value_name = "PANELS", value_delimiter = ',', default_values_t = HudPanel::ALL)]
+    pub hud: Vec<HudPanel>,
+
+    /// Seed for the weather particles, random if not given
+    #[arg(short, long)]
+    pub seed: Option<u64>,
+
+    /// Run without a window and print a JSON summary at the end
+    #[arg(long)]
+    pub headless: bool,
+
+    /// Stop a headless run after this many ticks
+    #[arg(
+        long,
+        value_name = "TICKS",
+        default_value_t = 1000,
+        requires = "headless"
+    )]
+    pub ticks: u64,
+
+    /// Write the headless summary to a file instead of stdout
+    #[arg(long, value_name = "FILE", requires = "headless")]
+    pub summary: Option<PathBuf>,
+
+    /// Directory for PNG frames of a headless run
+    #[arg(long, value_name = "DIR", requires = "headless")]
+    pub frames: Option<PathBuf>,
+
+    /// Write a frame every this many ticks, 0 for the last tick only
+    #[arg(long, value_name = "TICKS", default_value_t = 0, requires = "frames")]
+    pub frame_every: u64,
+
+    /// Size in pixels of one tile in the PNG frames
+    #[arg(long, value_name = "PIXELS", default_value_t = 16, value_parser = clap::value_parser!(u32).range(1..=64), requires = "frames")]
+    pub frame_tile_size: u32,
+}
+
+#[derive(ValueEnum, Clone, Copy, PartialEq, Debug)]
+pub enum MinimapCorner {
+    TopLeft,
+    TopRight,
+    BottomLeft,
+    BottomRight,
+}
+
+#[derive(ValueEnum, Clone, Copy, PartialEq, Debug)]
+pub enum HudPanel {
+    Energy,
+    Status,
+    Robot,
+    Fps,
+}
+
+impl HudPanel {
+    pub const ALL: [HudPanel; 4] = [
+        HudPanel::Energy,
+        HudPanel::Status,
+        HudPanel::Robot,
+        HudPanel::Fps,
+    ];
+
+    // function key that hides or shows the panel
+    pub fn key(self) -> KeyCode {
+        match self {
+            HudPanel::Energy => KeyCode::F1,
+            HudPanel::Status => KeyCode::F2,
+            HudPanel::Robot => KeyCode::F3,
+            HudPanel::Fps => KeyCode::F4,
+        }
+    }
+}
+
+impl Args {
+    // checks that need more than one argument at a time
+    pub fn validate(&self) -> Result<(), String> {
+        let mut seen = Vec::new();
+        for (key, _) in self.params.iter() {
+            if seen.contains(&key) {
+                return Err(format!("parameter '{}' given more than once", key));
+            }
+            seen.push(key);
+        }
+        Ok(())
+    }
+
+    // seed given on the command line, or a fresh one so runs can be repeated
+    pub fn seed(&self) -> u64 {
+        self.seed.unwrap_or_else(rand::random)
+    }
+}
+
+fn parse_existing_file(value: &str) -> Result<PathBuf, String> {
+    let path = PathBuf::from(value);
+    if !path.is_file() {
+        return Err(format!("file '{}' does not exist", value));
+    }
+    Ok(path)
+}
+
+fn parse_param(value: &str) -> Result<(String, String), String> {
+    match value.split_once('=') {
+        Some((key, value)) if !key.is_empty() => Ok((key.to_string(), value.to_string())),
+        _ => Err(format!("'{}' is not in the form KEY=VALUE", value)),
+    }
+}
+
+fn parse_tick_interval(value: &str) -> Result<f32, String> {
+    let seconds = value
+        .parse::<f32>()
+        .map_err(|_| format!("'{}' is not a number of seconds", value))?;
+    if !seconds.is_finite() || seconds <= 0.0 {
+        return Err(String::from("tick interval must be greater than zero"));
+    }
+    Ok(seconds)
+}
+
+fn parse_window_dimension(value: &str) -> Result<f32, String> {
+    let pixels = value
+        .parse::<f32>()
+        .map_err(|_| format!("'{}' is not a number of pixels", value))?;
+    if !pixels.is_finite() || pixels < 100.0 {
+        return Err(String::from(
+            "window dimensions must be at least 100 pixels",
+        ));
+    }
+    Ok(pixels)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(args: &[&str]) -> Result<Args, String> {
+        let args = Args::try_parse_from(std::iter::once("visualizer").chain(args.iter().copied()))
+            .map_err(|err| err.to_string())?;
+        args.validate()?;
+        Ok(args)
+    }
+
+    #[test]
+    fn a_world_or_replay_is_needed() {
+        assert!(parse(&[]).is_err());
+        assert!(parse(&["--world", "no/such/world.bin"]).is_err());
+        assert!(parse(&["--replay", "no/such/run.replay"]).is_err());
+    }
+
+    #[test]
+    fn replays_conflict_with_running_a_robot() {
+        let file = std::env::temp_dir().join(format!("visualizer-{}-cli", std::process::id()));
+        std::fs::write(&file, b"").unwrap();
+        let replay = file.to_str().unwrap();
+        let conflicts = [
+            parse(&["--replay", replay, "--world", replay]),
+            parse(&["--replay", replay, "--bot", "saver"]),
+            parse(&["--replay", replay, "-p", "coins=1"]),
+            parse(&["--replay", replay, "--record", "out.replay"]),
+        ];
+        let alone = parse(&["--replay", replay]);
+        std::fs::remove_file(&file).unwrap();
+        for result in conflicts {
+            assert!(result.is_err());
+        }
+        assert!(alone.is_ok());
+    }
+
+    #[test]
+    fn params_are_key_value_pairs() {
+        assert_eq!(
+            parse_param("coins=20"),
+            Ok((String::from("coins"), String::from("20")))
+        );
+        assert_eq!(
+            parse_param("name=a=b"),
+            Ok((String::from("name"), String::from("a=b")))
+        );
+        assert!(parse_param("coins").is_err());
+        assert!(parse_param("=20").is_err());
+    }
+
+    #[test]
+    fn params_are_given_once() {
+        let err = parse(&["--list-bots", "-p", "coins=1", "-p", "coins=2"]).unwrap_err();
+        assert!(err.contains("more than once"), "{}", err);
+    }
+
+    #[test]
+    fn numbers_are_checked() {
+        assert!(parse_tick_interval("0.5").is_ok());
+        assert!(parse_tick_interval("0").is_err());
+        assert!(parse_tick_interval("-1").is_err());
+        assert!(parse_tick_interval("inf").is_err());
+        assert!(parse_window_dimension("99").is_err());
+        assert!(parse_window_dimension("800").is_ok());
+    }
+
+    #[test]
+    fn headless_options_need_headless() {
+        assert!(parse(&["--list-bots", "--ticks", "5"]).is_err());
+        assert!(parse(&["--list-bots", "--headless", "--frame-every", "5"]).is_err());
+    }
+}
@@ -1,6 +1,73 @@
-use bevy::prelude::Component;
+use crate::charts::ChartKind;
+use crate::cli::HudPanel;
+use crate::event_log::EventKind;
+use bevy::prelude::{Component, Vec2};
 
-#[derive(Default, Component)]
-pub struct LastUpdate {
-    pub(crate) value: f64,
+// on-screen playback control
+#[derive(Component, Clone, Copy, Debug)]
+pub enum PlaybackButton {
+    TogglePause,
+    Step,
+    Slower,
+    Faster,
+    Max,
 }
+
+// shows or hides one kind of event in the event log
+#[derive(Component, Clone, Copy, Debug)]
+pub struct EventLogFilter(pub EventKind);
+
+// backpack panel row of the content with this atlas index
+#[derive(Component)]
+pub struct InventoryRow(pub u32);
+
+// a falling rain drop or snow flake
+#[derive(Component)]
+pub struct WeatherParticle {
+    pub(crate) velocity: Vec2,
+}
+
+// row of the robot sprite sheet
+#[derive(Clone, Copy, PartialEq, Debug, Default)]
+pub enum Facing {
+    #[default]
+    Down,
+    Left,
+    Right,
+    Up,
+}
+
+#[derive(Clone, Copy, PartialEq, Debug, Default)]
+pub enum MotionKind {
+    #[default]
+    Idle,
+    Walk,
+    Teleport,
+}
+
+// robot sprite moving from one tile to the next
+#[derive(Component, Default)]
+pub struct RobotMotion {
+    pub(crate) from: Vec2,
+    pub(crate) to: Vec2,
+    pub(crate) elapsed: f32,
+    pub(crate) duration: f32,
+    pub(crate) facing: Facing,
+    pub(crate) kind: MotionKind,
+}
+
+// a hud panel that f1 to f4 can hide
+#[derive(Component)]
+pub struct HudSection(pub HudPanel);
+
+// text of a hud panel
+#[derive(Component)]
+pub struct HudText(pub HudPanel);
+
+// line chart drawn into its own image
+#[derive(Component)]
+pub struct ChartImage(pub ChartKind);
+
+// title and latest values above a chart
+#[derive(Component)]
+pub struct ChartLabel(pub ChartKind);
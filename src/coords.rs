@@ -0,0 +1,206 @@
+use bevy::math::Vec2;
+use bevy_ecs_tilemap::map::TilemapSize;
+use bevy_ecs_tilemap::tiles::TilePos;
+
+// size of a tile in world units, same as the atlas tiles
+pub const TILE_PIXEL_SIZE: f32 = 64.0;
+// the robot sprite is drawn a bit off the tile center so it stands on it
+pub const ROBOT_SPRITE_OFFSET: Vec2 = Vec2::new(5.0, 10.0);
+
+// converts between robotics_lib (row, col), tilemap TilePos and world space.
+// rows grow downwards from the top of the map, TilePos y and world y grow upwards,
+// and tile (0, 0) of the tilemap is centered on the world origin
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub struct MapGrid {
+    pub rows: u32,
+    pub cols: u32,
+}
+
+impl MapGrid {
+    pub fn new(rows: u32, cols: u32) -> Self {
+        Self { rows, cols }
+    }
+
+    // rows are as many as the map has, columns as many as its widest row
+    pub fn of<T>(map: &[Vec<T>]) -> Self {
+        Self::new(
+            map.len() as u32,
+            map.iter().map(|row| row.len()).max().unwrap_or(0) as u32,
+        )
+    }
+
+    pub fn contains(&self, row: usize, col: usize) -> bool {
+        row < self.rows as usize && col < self.cols as usize
+    }
+
+    pub fn tilemap_size(&self) -> TilemapSize {
+        TilemapSize {
+            x: self.cols,
+            y: self.rows,
+        }
+    }
+
+    // every (row, col) of the map, row by row
+    pub fn cells(&self) -> impl Iterator<Item = (usize, usize)> {
+        let cols = self.cols as usize;
+        (0..self.rows as usize).flat_map(move |row| (0..cols).map(move |col| (row, col)))
+    }
+
+    pub fn tile_pos(&self, row: usize, col: usize) -> Option<TilePos> {
+        if !self.contains(row, col) {
+            return None;
+        }
+        Some(TilePos {
+            x: col as u32,
+            y: self.rows - 1 - row as u32,
+        })
+    }
+
+    // world position of the center of a cell, also for cells outside the map
+    pub fn cell_center(&self, row: usize, col: usize) -> Vec2 {
+        Vec2::new(
+            col as f32 * TILE_PIXEL_SIZE,
+            (self.rows as f32 - 1.0 - row as f32) * TILE_PIXEL_SIZE,
+        )
+    }
+
+    // cell under a world position, None outside the map
+    pub fn cell_at(&self, world: Vec2) -> Option<(usize, usize)> {
+        let x = (world.x / TILE_PIXEL_SIZE + 0.5).floor();
+        let y = (world.y / TILE_PIXEL_SIZE + 0.5).floor();
+        if x < 0.0 || y < 0.0 || x >= self.cols as f32 || y >= self.rows as f32 {
+            return None;
+        }
+        Some((self.rows as usize - 1 - y as usize, x as usize))
+    }
+
+    // world position of the robot sprite standing on a cell
+    pub fn robot_translation(&self, row: usize, col: usize) -> Vec2 {
+        self.cell_center(row, col) + ROBOT_SPRITE_OFFSET
+    }
+
+    // world size of the whole map
+    pub fn pixel_size(&self) -> Vec2 {
+        Vec2::new(
+            self.cols as f32 * TILE_PIXEL_SIZE,
+            self.rows as f32 * TILE_PIXEL_SIZE,
+        )
+    }
+
+    // world position of the middle of the map
+    pub fn center(&self) -> Vec2 {
+        (self.pixel_size() - Vec2::splat(TILE_PIXEL_SIZE)) / 2.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn grid_of_a_map() {
+        assert_eq!(MapGrid::of(&vec![vec![0; 5]; 3]), MapGrid::new(3, 5));
+        assert_eq!(MapGrid::of(&vec![vec![0; 2]; 7]), MapGrid::new(7, 2));
+        // ragged rows take the widest one
+        assert_eq!(
+            MapGrid::of(&[vec![0; 2], vec![0; 4], vec![0; 1]]),
+            MapGrid::new(3, 4)
+        );
+        assert_eq!(MapGrid::of::<u8>(&[]), MapGrid::new(0, 0));
+        assert_eq!(MapGrid::of::<u8>(&[vec![], vec![]]), MapGrid::new(2, 0));
+    }
+
+    #[test]
+    fn tile_pos_flips_rows() {
+        let grid = MapGrid::new(3, 5);
+        assert_eq!(grid.tile_pos(0, 0), Some(TilePos { x: 0, y: 2 }));
+        assert_eq!(grid.tile_pos(2, 0), Some(TilePos { x: 0, y: 0 }));
+        assert_eq!(grid.tile_pos(0, 4), Some(TilePos { x: 4, y: 2 }));
+        assert_eq!(grid.tile_pos(2, 4), Some(TilePos { x: 4, y: 0 }));
+    }
+
+    #[test]
+    fn tile_pos_outside_the_map() {
+        let grid = MapGrid::new(3, 5);
+        assert_eq!(grid.tile_pos(3, 0), None);
+        assert_eq!(grid.tile_pos(0, 5), None);
+        assert_eq!(MapGrid::new(0, 0).tile_pos(0, 0), None);
+    }
+
+    #[test]
+    fn cells_cover_the_map_row_by_row() {
+        let cells: Vec<_> = MapGrid::new(2, 3).cells().collect();
+        assert_eq!(cells, vec![(0, 0), (0, 1), (0, 2), (1, 0), (1, 1), (1, 2)]);
+        assert_eq!(MapGrid::new(0, 3).cells().count(), 0);
+        assert_eq!(MapGrid::new(3, 0).cells().count(), 0);
+    }
+
+    #[test]
+    fn tilemap_size_is_cols_by_rows() {
+        let size = MapGrid::new(3, 5).tilemap_size();
+        assert_eq!((size.x, size.y), (5, 3));
+    }
+
+    #[test]
+    fn cell_center_matches_tile_pos() {
+        let grid = MapGrid::new(3, 5);
+        for (row, col) in grid.cells() {
+            let tile_pos = grid.tile_pos(row, col).unwrap();
+            let expected = Vec2::new(tile_pos.x as f32, tile_pos.y as f32) * TILE_PIXEL_SIZE;
+            assert_eq!(grid.cell_center(row, col), expected);
+        }
+    }
+
+    #[test]
+    fn cell_at_picks_the_tile_under_the_point() {
+        let grid = MapGrid::new(3, 5);
+        for (row, col) in grid.cells() {
+            let center = grid.cell_center(row, col);
+            assert_eq!(grid.cell_at(center), Some((row, col)));
+            // just inside the corners of the tile
+            let inside = TILE_PIXEL_SIZE / 2.0 - 0.01;
+            assert_eq!(
+                grid.cell_at(center + Vec2::new(inside, inside)),
+                Some((row, col))
+            );
+            assert_eq!(
+                grid.cell_at(center - Vec2::new(inside, inside)),
+                Some((row, col))
+            );
+        }
+    }
+
+    #[test]
+    fn cell_at_outside_the_map() {
+        let grid = MapGrid::new(3, 5);
+        let half = TILE_PIXEL_SIZE / 2.0;
+        assert_eq!(grid.cell_at(Vec2::new(-half - 0.01, 0.0)), None);
+        assert_eq!(grid.cell_at(Vec2::new(0.0, -half - 0.01)), None);
+        assert_eq!(
+            grid.cell_at(Vec2::new(5.0 * TILE_PIXEL_SIZE - half, 0.0)),
+            None
+        );
+        assert_eq!(
+            grid.cell_at(Vec2::new(0.0, 3.0 * TILE_PIXEL_SIZE - half)),
+            None
+        );
+        assert_eq!(MapGrid::new(0, 0).cell_at(Vec2::ZERO), None);
+    }
+
+    #[test]
+    fn robot_stands_on_its_cell() {
+        let grid = MapGrid::new(3, 5);
+        let translation = grid.robot_translation(0, 4);
+        assert_eq!(translation, grid.cell_center(0, 4) + ROBOT_SPRITE_OFFSET);
+        assert_eq!(grid.cell_at(translation), Some((0, 4)));
+    }
+
+    #[test]
+    fn center_of_the_map() {
+        assert_eq!(MapGrid::new(1, 1).center(), Vec2::ZERO);
+        assert_eq!(
+            MapGrid::new(3, 5).center(),
+            Vec2::new(2.0 * TILE_PIXEL_SIZE, TILE_PIXEL_SIZE)
+        );
+    }
+}
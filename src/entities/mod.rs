@@ -4,7 +4,13 @@ use bevy::prelude::Component;
 pub struct VisualizerRobot;
 
 #[derive(Component)]
-pub struct MiniCamera;
+pub struct MinimapPanel;
+
+#[derive(Component)]
+pub struct MinimapView;
+
+#[derive(Component)]
+pub struct MinimapRobot;
 
 #[derive(Component)]
 pub struct ContentMap;
@@ -19,4 +25,79 @@ pub struct DncRectangle;
 pub struct BigBrother;
 
 #[derive(Component)]
-pub struct HUD;
+pub struct Hud;
+
+#[derive(Component)]
+pub struct PlaybackText;
+
+#[derive(Component)]
+pub struct TimelineSlider;
+
+#[derive(Component)]
+pub struct TimelineFill;
+
+#[derive(Component)]
+pub struct TimelineText;
+
+#[derive(Component)]
+pub struct EventLogPanel;
+
+#[derive(Component)]
+pub struct EventLogText;
+
+#[derive(Component)]
+pub struct InventoryText;
+
+#[derive(Component)]
+pub struct InventoryFill;
+
+#[derive(Component)]
+pub struct InventoryRows;
+
+#[derive(Component)]
+pub struct WeatherOverlay;
+
+#[derive(Component)]
+pub struct WeatherIcon;
+
+#[derive(Component)]
+pub struct WeatherText;
+
+#[derive(Component)]
+pub struct FogMap;
+
+#[derive(Component)]
+pub struct DiscoveryText;
+
+#[derive(Component)]
+pub struct TileLayer;
+
+#[derive(Component)]
+pub struct ContentLayer;
+
+#[derive(Component)]
+pub struct FogLayer;
+
+#[derive(Component)]
+pub struct ContentLabel;
+
+#[derive(Component)]
+pub struct InspectorPanel;
+
+#[derive(Component)]
+pub struct InspectorText;
+
+#[derive(Component)]
+pub struct OverlayLayer;
+
+#[derive(Component)]
+pub struct OverlayMap;
+
+#[derive(Component)]
+pub struct OverlayText;
+
+#[derive(Component)]
+pub struct ChartPanel;
+
+#[derive(Component)]
+pub struct ChartTimeText;
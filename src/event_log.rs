@@ -0,0 +1,162 @@
+use std::collections::{HashSet, VecDeque};
+
+use bevy::prelude::{Color, Resource};
+
+use crate::snapshot::TickSnapshot;
+use crate::tiles::content_name;
+use crate::world::Event;
+
+// oldest entries are dropped past this
+const MAX_LOG_ENTRIES: usize = 10_000;
+
+// groups of robot events, each can be hidden in the event log panel
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum EventKind {
+    EnergyConsumed,
+    EnergyRecharged,
+    Backpack,
+    TileContent,
+    Moved,
+    Time,
+    Status,
+}
+
+impl EventKind {
+    pub const ALL: [EventKind; 7] = [
+        EventKind::EnergyConsumed,
+        EventKind::EnergyRecharged,
+        EventKind::Backpack,
+        EventKind::TileContent,
+        EventKind::Moved,
+        EventKind::Time,
+        EventKind::Status,
+    ];
+
+    pub fn of(event: &Event) -> EventKind {
+        match event {
+            Event::EnergyConsumed(_) => EventKind::EnergyConsumed,
+            Event::EnergyRecharged(_) => EventKind::EnergyRecharged,
+            Event::AddedToBackpack(_, _) | Event::RemovedFromBackpack(_, _) => EventKind::Backpack,
+            Event::TileContentUpdated(_, _) => EventKind::TileContent,
+            Event::Moved(_, _) => EventKind::Moved,
+            Event::TimeChanged(_) | Event::DayChanged(_) => EventKind::Time,
+            Event::Ready | Event::Terminated => EventKind::Status,
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            EventKind::EnergyConsumed => "consumed",
+            EventKind::EnergyRecharged => "recharged",
+            EventKind::Backpack => "backpack",
+            EventKind::TileContent => "contents",
+            EventKind::Moved => "moves",
+            EventKind::Time => "time",
+            EventKind::Status => "status",
+        }
+    }
+
+    pub fn color(self) -> Color {
+        match self {
+            EventKind::EnergyConsumed => Color::rgb(1.0, 0.4, 0.4),
+            EventKind::EnergyRecharged => Color::rgb(0.4, 1.0, 0.4),
+            EventKind::Backpack => Color::rgb(1.0, 0.85, 0.3),
+            EventKind::TileContent => Color::rgb(0.8, 0.5, 1.0),
+            EventKind::Moved => Color::rgb(0.5, 0.8, 1.0),
+            EventKind::Time => Color::rgb(0.7, 0.7, 0.7),
+            EventKind::Status => Color::WHITE,
+        }
+    }
+}
+
+pub struct LogEntry {
+    pub tick: u64,
+    pub kind: EventKind,
+    pub text: String,
+}
+
+// every robot event seen so far, with the tick it arrived in
+#[derive(Resource, Default)]
+pub struct EventLog {
+    entries: VecDeque<LogEntry>,
+    last_tick: Option<u64>,
+    pub(crate) hidden: HashSet<EventKind>,
+    // lines scrolled up from the newest one
+    pub(crate) scroll: usize,
+}
+
+impl EventLog {
+    // only ticks newer than the last one added are taken
+    pub fn push(&mut self, snapshot: &TickSnapshot) {
+        if self.last_tick.is_some_and(|last| snapshot.tick <= last) {
+            return;
+        }
+        self.last_tick = Some(snapshot.tick);
+        for event in snapshot.events.iter() {
+            self.entries.push_back(LogEntry {
+                tick: snapshot.tick,
+                kind: EventKind::of(event),
+                text: describe(event),
+            });
+        }
+        while self.entries.len() > MAX_LOG_ENTRIES {
+            self.entries.pop_front();
+        }
+    }
+
+    // a line that isn't a robot event, shown at the last tick added
+    pub fn note(&mut self, text: String) {
+        self.entries.push_back(LogEntry {
+            tick: self.last_tick.unwrap_or(0),
+            kind: EventKind::Status,
+            text,
+        });
+    }
+
+    pub fn last_tick(&self) -> Option<u64> {
+        self.last_tick
+    }
+
+    pub fn toggle(&mut self, kind: EventKind) {
+        if !self.hidden.remove(&kind) {
+            self.hidden.insert(kind);
+        }
+    }
+
+    // shown entries up to `tick`, newest first
+    pub fn visible(&self, tick: u64) -> impl Iterator<Item = &LogEntry> {
+        self.entries
+            .iter()
+            .rev()
+            .filter(move |entry| entry.tick <= tick && !self.hidden.contains(&entry.kind))
+    }
+}
+
+fn describe(event: &Event) -> String {
+    match event {
+        Event::Ready => String::from("robot ready"),
+        Event::Terminated => String::from("robot terminated"),
+        Event::TimeChanged(environment) => format!(
+            "time {} {:?}",
+            environment.get_time_of_day_string(),
+            environment.get_weather_condition()
+        ),
+        Event::DayChanged(environment) => {
+            format!("new day, {:?}", environment.get_weather_condition())
+        }
+        Event::EnergyRecharged(amount) => format!("recharged {} energy", amount),
+        Event::EnergyConsumed(amount) => format!("consumed {} energy", amount),
+        Event::Moved(tile, (row, col)) => {
+            format!("moved to ({}, {}) {:?}", row, col, tile.tile_type)
+        }
+        Event::TileContentUpdated(tile, (row, col)) => {
+            format!("({}, {}) now has {}", row, col, content_name(&tile.content))
+        }
+        Event::AddedToBackpack(content, amount) => {
+            format!("+{} {} to backpack", amount, content_name(content))
+        }
+        Event::RemovedFromBackpack(content, amount) => {
+            format!("-{} {} from backpack", amount, content_name(content))
+        }
+    }
+}
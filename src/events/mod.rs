@@ -1,7 +1,17 @@
+use crate::snapshot::TickSnapshot;
 use bevy::prelude::Event;
+use std::sync::Arc;
 
 #[derive(Event)]
-pub struct TickEvent;
+pub struct TickEvent(pub Arc<TickSnapshot>);
 
 #[derive(Event)]
 pub struct CameraEvent;
+
+// cells of the map on screen that need to be drawn again
+#[derive(Event)]
+pub enum MapDiffEvent {
+    All,
+    // (row, col)
+    Cells(Vec<(usize, usize)>),
+}
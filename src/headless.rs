@@ -0,0 +1,268 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use image::imageops::{self, FilterType};
+use image::{Rgba, RgbaImage};
+use serde::Serialize;
+
+use crate::cli::Args;
+use crate::coords::MapGrid;
+use crate::replay::ReplayWriter;
+use crate::simulation::{replay_source, RunnerSource, TickOutcome};
+use crate::snapshot::TickSnapshot;
+use crate::tiles::{
+    content_name, content_texture_index, tile_texture_index, CONTENTS_ATLAS, ROBOT_SPRITE,
+    TILES_ATLAS,
+};
+use crate::world::{Content, Event};
+use crate::NO_RUNNER;
+
+const ASSETS_DIR: &str = "assets";
+const ATLAS_TILE_SIZE: u32 = 64;
+// color of undiscovered tiles in the frames
+const FOG_COLOR: Rgba<u8> = Rgba([18, 20, 32, 255]);
+
+// what gets written to --summary at the end of a headless run
+#[derive(Serialize, Default)]
+struct RunSummary {
+    ticks: u64,
+    terminated: bool,
+    final_energy: usize,
+    backpack_size: usize,
+    backpack: BTreeMap<String, usize>,
+    coins: usize,
+    coins_collected: usize,
+    distance_travelled: usize,
+    tiles_discovered: usize,
+    tiles_total: usize,
+}
+
+impl RunSummary {
+    fn record(&mut self, snapshot: &TickSnapshot, previous_position: Option<(usize, usize)>) {
+        self.ticks = snapshot.tick;
+        self.final_energy = snapshot.energy;
+        self.backpack_size = snapshot.backpack.size;
+        self.backpack = BTreeMap::new();
+        self.coins = 0;
+        for (content, amount) in snapshot.backpack.contents.iter() {
+            *self
+                .backpack
+                .entry(content_name(content).to_string())
+                .or_insert(0) += amount;
+            if matches!(content, Content::Coin(_)) {
+                self.coins += amount;
+            }
+        }
+        // one tile per move, a teleport is a single move however far it goes
+        let mut position = previous_position;
+        for event in snapshot.events.iter() {
+            match event {
+                Event::AddedToBackpack(Content::Coin(_), amount) => self.coins_collected += amount,
+                Event::Moved(_, to) if position != Some(*to) => {
+                    if position.is_some() {
+                        self.distance_travelled += 1;
+                    }
+                    position = Some(*to);
+                }
+                Event::Terminated => self.terminated = true,
+                _ => {}
+            }
+        }
+        self.tiles_total = snapshot.map.iter().map(|row| row.len()).sum();
+        self.tiles_discovered = snapshot.map.iter().flatten().flatten().count();
+    }
+}
+
+// run the robot (or replay) without a window until --ticks or termination
+pub fn run(args: &Args, runner: Option<RunnerSource>) -> Result<(), String> {
+    let (sender, receiver) = crossbeam_channel::unbounded();
+    let mut source = match (&args.replay, runner) {
+        (Some(path), _) => replay_source(path, sender)?,
+        (None, Some(runner)) => runner(args, sender)?.0,
+        (None, None) => return Err(String::from(NO_RUNNER)),
+    };
+    let mut recorder = args
+        .record
+        .as_deref()
+        .map(ReplayWriter::create)
+        .transpose()?;
+    let renderer = args
+        .frames
+        .as_ref()
+        .map(|dir| FrameRenderer::new(dir, args.frame_tile_size))
+        .transpose()?;
+
+    let mut summary = RunSummary::default();
+    let mut last: Option<Arc<TickSnapshot>> = None;
+    let mut stopped = false;
+    // counted here as well, a failing tick doesn't send a snapshot
+    let mut ticks = 0;
+    while !stopped && !summary.terminated && ticks < args.ticks {
+        match source() {
+            TickOutcome::Ticked => {}
+            TickOutcome::Finished => stopped = true,
+            TickOutcome::Failed(err) => {
+                eprintln!("error: {}", err);
+                stopped = true;
+            }
+        }
+        ticks += 1;
+        for snapshot in receiver.try_iter() {
+            if let Some(recorder) = recorder.as_mut() {
+                recorder.write(&snapshot)?;
+            }
+            summary.record(&snapshot, last.as_ref().map(|last| last.robot_position));
+            if let Some(renderer) = renderer.as_ref() {
+                if args.frame_every > 0 && snapshot.tick % args.frame_every == 0 {
+                    renderer.save(&snapshot)?;
+                }
+            }
+            last = Some(snapshot);
+        }
+    }
+    if let Some(recorder) = recorder.as_mut() {
+        recorder.flush()?;
+    }
+    // final frame, unless it was just written
+    if let (Some(renderer), Some(last)) = (renderer.as_ref(), last.as_ref()) {
+        if args.frame_every == 0 || last.tick % args.frame_every != 0 {
+            renderer.save(last)?;
+        }
+    }
+
+    let json = serde_json::to_string_pretty(&summary)
+        .map_err(|err| format!("could not write summary: {}", err))?;
+    match &args.summary {
+        Some(path) => fs::write(path, json)
+            .map_err(|err| format!("could not write '{}': {}", path.display(), err))?,
+        None => println!("{}", json),
+    }
+    Ok(())
+}
+
+// draws tiles, contents and the robot with the same atlas indices as the gui
+struct FrameRenderer {
+    dir: PathBuf,
+    tile_size: u32,
+    tiles: Vec<RgbaImage>,
+    contents: Vec<RgbaImage>,
+    robot: RgbaImage,
+}
+
+impl FrameRenderer {
+    fn new(dir: &Path, tile_size: u32) -> Result<Self, String> {
+        fs::create_dir_all(dir)
+            .map_err(|err| format!("could not create '{}': {}", dir.display(), err))?;
+        Ok(Self {
+            dir: dir.to_path_buf(),
+            tile_size,
+            tiles: load_atlas(TILES_ATLAS, tile_size)?,
+            contents: load_atlas(CONTENTS_ATLAS, tile_size)?,
+            robot: load_atlas(ROBOT_SPRITE, tile_size)?.remove(0),
+        })
+    }
+
+    fn save(&self, snapshot: &TickSnapshot) -> Result<(), String> {
+        let path = self.dir.join(format!("tick_{:06}.png", snapshot.tick));
+        self.render(snapshot)
+            .save(&path)
+            .map_err(|err| format!("could not write '{}': {}", path.display(), err))
+    }
+
+    fn render(&self, snapshot: &TickSnapshot) -> RgbaImage {
+        let grid = MapGrid::of(&snapshot.map);
+        let mut frame = RgbaImage::from_pixel(
+            grid.cols * self.tile_size,
+            grid.rows * self.tile_size,
+            FOG_COLOR,
+        );
+        for (row, cells) in snapshot.map.iter().enumerate() {
+            for (col, cell) in cells.iter().enumerate() {
+                let x = col as i64 * self.tile_size as i64;
+                let y = row as i64 * self.tile_size as i64;
+                // undiscovered tiles are left as fog
+                let Some(cell) = cell else {
+                    continue;
+                };
+                let tile_index = tile_texture_index(&cell.tile_type);
+                let content_index = content_texture_index(&cell.content);
+                if let Some(tile) = self.tiles.get(tile_index as usize) {
+                    imageops::overlay(&mut frame, tile, x, y);
+                }
+                if content_index != 0 {
+                    if let Some(content) = self.contents.get(content_index as usize) {
+                        imageops::overlay(&mut frame, content, x, y);
+                    }
+                }
+            }
+        }
+        let (row, col) = snapshot.robot_position;
+        imageops::overlay(
+            &mut frame,
+            &self.robot,
+            col as i64 * self.tile_size as i64,
+            row as i64 * self.tile_size as i64,
+        );
+        frame
+    }
+}
+
+// split a single row atlas into tiles scaled to `tile_size`
+fn load_atlas(name: &str, tile_size: u32) -> Result<Vec<RgbaImage>, String> {
+    let path = Path::new(ASSETS_DIR).join(name);
+    let atlas = image::open(&path)
+        .map_err(|err| format!("could not load '{}': {}", path.display(), err))?
+        .to_rgba8();
+    let count = (atlas.width() / ATLAS_TILE_SIZE).max(1);
+    Ok((0..count)
+        .map(|index| {
+            let tile = imageops::crop_imm(
+                &atlas,
+                index * ATLAS_TILE_SIZE,
+                0,
+                ATLAS_TILE_SIZE.min(atlas.width()),
+                ATLAS_TILE_SIZE.min(atlas.height()),
+            )
+            .to_image();
+            imageops::resize(&tile, tile_size, tile_size, FilterType::Nearest)
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::snapshot::tests::{map, snapshot};
+    use crate::world::{Tile, TileType};
+
+    fn moved(to: (usize, usize)) -> Event {
+        let tile = Tile {
+            tile_type: TileType::Teleport(true),
+            content: Content::None,
+            elevation: 0,
+        };
+        Event::Moved(tile, to)
+    }
+
+    #[test]
+    fn distance_counts_moves() {
+        let mut summary = RunSummary::default();
+        let mut first = snapshot(1, map(20, 20, 0));
+        first.robot_position = (0, 0);
+        summary.record(&first, None);
+        // a step, a teleport across the map and a step back
+        let mut second = snapshot(2, map(20, 20, 0));
+        second.events = vec![moved((0, 1)), moved((15, 18)), moved((15, 17))];
+        second.robot_position = (15, 17);
+        summary.record(&second, Some(first.robot_position));
+        assert_eq!(summary.distance_travelled, 3);
+        // moving onto the tile it is on doesn't count
+        let mut third = snapshot(3, map(20, 20, 0));
+        third.events = vec![moved((15, 17))];
+        third.robot_position = (15, 17);
+        summary.record(&third, Some(second.robot_position));
+        assert_eq!(summary.distance_travelled, 3);
+    }
+}
@@ -0,0 +1,283 @@
+use std::collections::{HashMap, VecDeque};
+use std::sync::Arc;
+
+use bevy::prelude::Resource;
+
+use crate::snapshot::{apply_changes, map_changes, same_shape, CellChange, TickSnapshot};
+use crate::world::Tile;
+
+// full copies of the map kept over the whole history, the ticks between them are
+// rebuilt from their changes. long histories space them further apart instead of
+// keeping more, so memory stays bounded by a few maps
+const MAX_KEYFRAMES: usize = 16;
+// short histories still don't keep a map every few ticks
+const MIN_KEYFRAME_INTERVAL: usize = 64;
+
+struct HistoryEntry {
+    // map left empty, rebuilt from the keyframe and changes
+    snapshot: TickSnapshot,
+    changes: Vec<CellChange>,
+    keyframe: Option<Arc<Vec<Vec<Option<Tile>>>>>,
+}
+
+// every tick seen so far, oldest first
+#[derive(Resource)]
+pub struct SnapshotHistory {
+    entries: VecDeque<HistoryEntry>,
+    // ticks kept that changed each cell, oldest first
+    changed_at: HashMap<(usize, usize), VecDeque<u64>>,
+    last_map: Vec<Vec<Option<Tile>>>,
+    since_keyframe: usize,
+    keyframe_interval: usize,
+    capacity: usize,
+}
+
+impl SnapshotHistory {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            entries: VecDeque::new(),
+            changed_at: HashMap::new(),
+            last_map: Vec::new(),
+            since_keyframe: 0,
+            keyframe_interval: (capacity / MAX_KEYFRAMES).max(MIN_KEYFRAME_INTERVAL),
+            capacity: capacity.max(1),
+        }
+    }
+
+    pub fn push(&mut self, snapshot: &TickSnapshot) {
+        let needs_keyframe = self.entries.is_empty()
+            || self.since_keyframe >= self.keyframe_interval
+            || !same_shape(&self.last_map, &snapshot.map);
+        let entry = if needs_keyframe {
+            self.since_keyframe = 0;
+            // still remember what changed, unless the map changed shape
+            let changes = if same_shape(&self.last_map, &snapshot.map) {
+                map_changes(&self.last_map, &snapshot.map)
+            } else {
+                Vec::new()
+            };
+            self.last_map = snapshot.map.clone();
+            HistoryEntry {
+                snapshot: snapshot.without_map(),
+                changes,
+                keyframe: Some(Arc::new(snapshot.map.clone())),
+            }
+        } else {
+            self.since_keyframe += 1;
+            let changes = map_changes(&self.last_map, &snapshot.map);
+            let _ = apply_changes(&mut self.last_map, &changes);
+            HistoryEntry {
+                snapshot: snapshot.without_map(),
+                changes,
+                keyframe: None,
+            }
+        };
+        for (row, col, _) in entry.changes.iter() {
+            self.changed_at
+                .entry((*row, *col))
+                .or_default()
+                .push_back(snapshot.tick);
+        }
+        self.entries.push_back(entry);
+
+        while self.entries.len() > self.capacity {
+            // the new oldest entry must be able to rebuild its map on its own,
+            // its changes stay for last_change
+            if self.entries.len() > 1 && self.entries[1].keyframe.is_none() {
+                if let Some(map) = self.map_at(1) {
+                    self.entries[1].keyframe = Some(Arc::new(map));
+                }
+            }
+            let Some(oldest) = self.entries.pop_front() else {
+                break;
+            };
+            for (row, col, _) in oldest.changes.iter() {
+                if let Some(ticks) = self.changed_at.get_mut(&(*row, *col)) {
+                    ticks.pop_front();
+                    if ticks.is_empty() {
+                        self.changed_at.remove(&(*row, *col));
+                    }
+                }
+            }
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    // position of the entry with the given tick
+    pub fn index_of(&self, tick: u64) -> Option<usize> {
+        self.entries
+            .binary_search_by_key(&tick, |entry| entry.snapshot.tick)
+            .ok()
+    }
+
+    // entries without their map, cheap to walk through
+    pub fn iter(&self) -> impl DoubleEndedIterator<Item = &TickSnapshot> + ExactSizeIterator {
+        self.entries.iter().map(|entry| &entry.snapshot)
+    }
+
+    // tick of the newest entry up to `index` that changed the cell, None if it
+    // didn't change since the oldest entry kept
+    pub fn last_change(&self, index: usize, row: usize, col: usize) -> Option<u64> {
+        let tick = self.entries.get(index)?.snapshot.tick;
+        let ticks = self.changed_at.get(&(row, col))?;
+        let end = ticks.partition_point(|changed| *changed <= tick);
+        end.checked_sub(1).map(|last| ticks[last])
+    }
+
+    // the full snapshot with its map rebuilt
+    pub fn snapshot_at(&self, index: usize) -> Option<Arc<TickSnapshot>> {
+        let map = self.map_at(index)?;
+        let entry = &self.entries[index];
+        let mut snapshot = entry.snapshot.without_map();
+        snapshot.map = map;
+        // keyframes don't know what changed since the tick before them
+        if entry.keyframe.is_none() {
+            snapshot.changes = Some(entry.changes.clone());
+        }
+        Some(Arc::new(snapshot))
+    }
+
+    fn map_at(&self, index: usize) -> Option<Vec<Vec<Option<Tile>>>> {
+        if index >= self.entries.len() {
+            return None;
+        }
+        let keyframe_index = (0..=index)
+            .rev()
+            .find(|i| self.entries[*i].keyframe.is_some())?;
+        let mut map = self.entries[keyframe_index]
+            .keyframe
+            .as_ref()
+            .map(|keyframe| (**keyframe).clone())?;
+        for entry in self.entries.range(keyframe_index + 1..=index) {
+            apply_changes(&mut map, &entry.changes).ok()?;
+        }
+        Some(map)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::snapshot::tests::{map, snapshot};
+
+    // one cell changes every tick, its elevation is the tick
+    fn run(ticks: u64, rows: usize, cols: usize) -> Vec<TickSnapshot> {
+        let mut current = map(rows, cols, 0);
+        (1..=ticks)
+            .map(|tick| {
+                let cell = tick as usize % (rows * cols);
+                current[cell / cols][cell % cols] = map(1, 1, tick as usize)[0][0].clone();
+                snapshot(tick, current.clone())
+            })
+            .collect()
+    }
+
+    fn assert_rebuilds(history: &SnapshotHistory, run: &[TickSnapshot]) {
+        for (index, kept) in history.iter().enumerate() {
+            let expected = run.iter().find(|s| s.tick == kept.tick).unwrap();
+            let rebuilt = history.snapshot_at(index).unwrap();
+            assert_eq!(rebuilt.tick, expected.tick);
+            assert_eq!(rebuilt.map, expected.map, "tick {}", expected.tick);
+        }
+    }
+
+    #[test]
+    fn every_tick_rebuilds_its_map() {
+        let run = run(300, 4, 5);
+        let mut history = SnapshotHistory::new(1000);
+        for snapshot in run.iter() {
+            history.push(snapshot);
+        }
+        assert_eq!(history.len(), 300);
+        assert_rebuilds(&history, &run);
+        assert!(history.snapshot_at(300).is_none());
+    }
+
+    #[test]
+    fn oldest_ticks_are_dropped() {
+        let run = run(400, 4, 5);
+        let mut history = SnapshotHistory::new(150);
+        for snapshot in run.iter() {
+            history.push(snapshot);
+        }
+        assert_eq!(history.len(), 150);
+        assert_eq!(history.iter().next().unwrap().tick, 251);
+        assert_eq!(history.index_of(250), None);
+        assert_eq!(history.index_of(251), Some(0));
+        assert_eq!(history.index_of(400), Some(149));
+        assert_rebuilds(&history, &run);
+    }
+
+    #[test]
+    fn changes_lead_from_the_tick_before() {
+        let run = run(300, 4, 5);
+        let mut history = SnapshotHistory::new(1000);
+        for snapshot in run.iter() {
+            history.push(snapshot);
+        }
+        let mut with_changes = 0;
+        for (index, expected) in run.iter().enumerate().skip(1) {
+            let Some(changes) = history.snapshot_at(index).unwrap().changes.clone() else {
+                continue;
+            };
+            let mut map = history.snapshot_at(index - 1).unwrap().map.clone();
+            apply_changes(&mut map, &changes).unwrap();
+            assert_eq!(map, expected.map);
+            with_changes += 1;
+        }
+        assert!(with_changes > 0);
+    }
+
+    #[test]
+    fn last_change_of_a_cell() {
+        let run = run(300, 4, 5);
+        let mut history = SnapshotHistory::new(1000);
+        for snapshot in run.iter() {
+            history.push(snapshot);
+        }
+        // row 1, col 2 changes on every tick that is 7 modulo 20, keyframes included
+        assert_eq!(history.last_change(299, 1, 2), Some(287));
+        assert_eq!(history.last_change(100, 1, 2), Some(87));
+        assert_eq!(history.last_change(5, 1, 2), None);
+    }
+
+    #[test]
+    fn maps_can_change_shape() {
+        let mut ticks = run(100, 2, 2);
+        let mut grown = run(100, 3, 4);
+        for snapshot in grown.iter_mut() {
+            snapshot.tick += 100;
+        }
+        ticks.extend(grown);
+        let mut history = SnapshotHistory::new(1000);
+        for snapshot in ticks.iter() {
+            history.push(snapshot);
+        }
+        assert_rebuilds(&history, &ticks);
+    }
+
+    #[test]
+    fn last_change_forgets_dropped_ticks() {
+        let run = run(50, 4, 5);
+        let mut history = SnapshotHistory::new(25);
+        for snapshot in run.iter() {
+            history.push(snapshot);
+        }
+        // (0, 3) changes at ticks 23 and 43, only 26 to 50 are kept
+        let index = |tick| history.index_of(tick).unwrap();
+        assert_eq!(history.last_change(index(30), 0, 3), None);
+        assert_eq!(history.last_change(index(50), 0, 3), Some(43));
+        // (1, 2) changes at tick 27, right after the oldest one kept
+        assert_eq!(history.last_change(index(27), 1, 2), Some(27));
+        // one change for each tick kept
+        let tracked: usize = history.changed_at.values().map(|ticks| ticks.len()).sum();
+        assert_eq!(tracked, 25);
+    }
+}
@@ -0,0 +1,300 @@
+/*
+visualizer
+advanced programming course 23-24
+university of trento
+
+https://github.com/davidepaci
+*/
+
+// the gui and replays, robots are run by the crate in runner/ which calls run()
+// with its tick source, so this crate builds without the private registry
+
+// bevy systems take every resource and query they use as an argument
+#![allow(clippy::too_many_arguments)]
+
+// custom bevy stuff
+mod charts;
+pub mod cli;
+mod components;
+mod coords;
+mod entities;
+mod event_log;
+mod events;
+mod headless;
+mod history;
+mod lighting;
+mod replay;
+mod resources;
+pub mod simulation;
+pub mod snapshot;
+mod systems;
+mod tiles;
+pub mod world;
+
+// robotics lib stuff
+use crate::charts::ChartData;
+use crate::cli::Args;
+use crate::event_log::EventLog;
+use crate::history::SnapshotHistory;
+use crate::lighting::LightingGradient;
+use crate::replay::{ReplayRecorder, ReplayWriter};
+use crate::resources::{
+    CameraControl, ContentLabels, FogState, FogView, GroundTruth, InspectedTile,
+    InventoryHighlights, PlaybackState, RandomSource, RobotTrail, TerrainOverlay, Timeline,
+};
+use crate::simulation::{shutdown_simulation, RunnerSource, Simulation};
+use crate::snapshot::{CurrentSnapshot, SnapshotReceiver};
+use crate::systems::receive_snapshots;
+use crate::systems::update_contents;
+use crate::systems::update_dnc;
+
+// bevy engine
+use bevy::{
+    diagnostic::{FrameTimeDiagnosticsPlugin, LogDiagnosticsPlugin},
+    prelude::*,
+    window::WindowResolution,
+};
+
+use bevy_ecs_tilemap::prelude::*;
+
+// custom bevy stuff pt. 2
+use crate::events::{CameraEvent, MapDiffEvent, TickEvent};
+
+use resources::MapInfo;
+
+use clap::error::ErrorKind;
+use clap::{CommandFactory, Parser};
+
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+
+use std::process;
+
+use systems::camera::camera_controls;
+use systems::charts::{chart_controls, setup_charts, update_charts};
+use systems::diff_snapshots;
+use systems::event_log::{
+    collect_events, event_log_filters, log_simulation_failure, scroll_event_log, setup_event_log,
+    update_event_log,
+};
+use systems::fog::{fade_discoveries, fog_keyboard, setup_discovery_text, update_fog};
+use systems::follow_robot_camera;
+use systems::hud::{hud_keyboard, setup_hud, update_hud};
+use systems::inspector::{pick_tile, setup_inspector, update_inspector};
+use systems::inventory::{fade_inventory_highlights, setup_inventory, update_inventory};
+use systems::labels::{content_labels_keyboard, update_content_labels};
+use systems::minimap::{
+    minimap_click, minimap_keyboard, setup_minimap, update_minimap_image, update_minimap_markers,
+};
+use systems::overlay::{draw_contours, overlay_keyboard, setup_overlay_text, update_overlay};
+use systems::playback::{
+    playback_buttons, playback_keyboard, setup_playback_controls, update_playback_text,
+};
+use systems::startup;
+use systems::timeline::{
+    setup_timeline, show_timeline_snapshot, timeline_keyboard, timeline_playback, timeline_slider,
+    update_timeline_bar,
+};
+use systems::trail::{collect_trail, draw_trail, trail_keyboard};
+use systems::update_tilemap;
+use systems::weather::{
+    setup_weather, spawn_weather_particles, update_weather, update_weather_particles,
+};
+use systems::{animate_robot, update_robot_position};
+
+const NO_RUNNER: &str =
+    "this build only plays replays, use --replay or run robots with the runner crate in runner/";
+
+// parse command line, clap exits with a usage message on bad input
+pub fn parse_args() -> Args {
+    let args = Args::parse();
+    if let Err(err) = args.validate() {
+        Args::command()
+            .error(ErrorKind::ArgumentConflict, err)
+            .exit();
+    }
+    args
+}
+
+// open the window (or run headless) and show the robot given by `runner`, or the
+// replay in the args
+pub fn run(args: Args, runner: Option<RunnerSource>) {
+    if args.headless {
+        if let Err(err) = headless::run(&args, runner) {
+            eprintln!("error: {}", err);
+            process::exit(1);
+        }
+        return;
+    }
+    let seed = args.seed();
+
+    // create bot and world before opening the window, or play a recorded run
+    let simulation = match (&args.replay, runner) {
+        (Some(path), _) => Simulation::spawn_replay(path.clone(), args.tick_interval),
+        (None, Some(runner)) => Simulation::spawn(&args, runner),
+        (None, None) => Err(String::from(NO_RUNNER)),
+    };
+    let (mut simulation, snapshots) = match simulation {
+        Ok(simulation) => simulation,
+        Err(err) => {
+            eprintln!("error: {}", err);
+            process::exit(1);
+        }
+    };
+    // replays only know what the robot saw
+    let ground_truth = GroundTruth(simulation.take_ground_truth());
+    let lighting = match &args.lighting {
+        Some(path) => match LightingGradient::load(path) {
+            Ok(gradient) => gradient,
+            Err(err) => {
+                eprintln!("error: {}", err);
+                process::exit(1);
+            }
+        },
+        None => LightingGradient::default(),
+    };
+    let recorder = match &args.record {
+        Some(path) => match ReplayWriter::create(path) {
+            Ok(writer) => Some(ReplayRecorder(writer)),
+            Err(err) => {
+                eprintln!("error: {}", err);
+                process::exit(1);
+            }
+        },
+        None => None,
+    };
+
+    let mut app = App::new();
+    if let Some(recorder) = recorder {
+        app.insert_resource(recorder);
+    }
+    app.add_plugins(
+        DefaultPlugins
+            .set(WindowPlugin {
+                primary_window: Some(Window {
+                    title: String::from("Visualizer"),
+                    resolution: WindowResolution::new(args.width, args.height),
+                    ..Default::default()
+                }),
+                ..default()
+            })
+            .set(ImagePlugin::default_nearest()),
+    );
+    // logged once the log plugin is up, so the run can be repeated
+    info!("seed: {}", seed);
+    app.add_plugins(LogDiagnosticsPlugin::default())
+        .add_plugins(FrameTimeDiagnosticsPlugin)
+        .add_plugins(TilemapPlugin)
+        .insert_resource(RandomSource(StdRng::seed_from_u64(seed)))
+        .insert_resource(PlaybackState::new(args.tick_interval))
+        .insert_resource(lighting)
+        .insert_resource(simulation)
+        .insert_resource(SnapshotReceiver(snapshots))
+        .init_resource::<CurrentSnapshot>()
+        .insert_resource(SnapshotHistory::new(args.history))
+        .insert_resource(ChartData::new(args.history))
+        .init_resource::<Timeline>()
+        .init_resource::<EventLog>()
+        .init_resource::<InventoryHighlights>()
+        .init_resource::<RobotTrail>()
+        .init_resource::<FogView>()
+        .init_resource::<FogState>()
+        .init_resource::<ContentLabels>()
+        .init_resource::<InspectedTile>()
+        .init_resource::<TerrainOverlay>()
+        .init_resource::<CameraControl>()
+        .insert_resource(ground_truth)
+        .insert_resource(args)
+        .insert_resource(MapInfo {
+            rows: 0,
+            cols: 0,
+            last_known_robot_position: (0, 0),
+            current_time: (0, 0),
+        })
+        .add_event::<TickEvent>()
+        .add_event::<CameraEvent>()
+        .add_event::<MapDiffEvent>()
+        .add_systems(PreStartup, receive_snapshots)
+        .add_systems(Startup, startup)
+        .add_systems(Startup, setup_minimap.after(startup))
+        .add_systems(Startup, setup_hud)
+        .add_systems(Startup, setup_playback_controls)
+        .add_systems(Startup, setup_timeline)
+        .add_systems(Startup, setup_event_log)
+        .add_systems(Startup, setup_inventory)
+        .add_systems(Startup, setup_weather.after(startup))
+        .add_systems(Startup, setup_discovery_text)
+        .add_systems(Startup, setup_inspector)
+        .add_systems(Startup, setup_overlay_text)
+        .add_systems(Startup, setup_charts)
+        .add_systems(
+            PreUpdate,
+            (
+                receive_snapshots,
+                timeline_keyboard,
+                timeline_slider,
+                timeline_playback,
+                show_timeline_snapshot,
+                diff_snapshots,
+            )
+                .chain(),
+        )
+        .add_systems(Update, update_tilemap)
+        .add_systems(Update, update_contents)
+        .add_systems(
+            Update,
+            (content_labels_keyboard, update_content_labels).chain(),
+        )
+        .add_systems(Update, (update_robot_position, animate_robot).chain())
+        .add_systems(Update, update_dnc)
+        .add_systems(Update, (camera_controls, follow_robot_camera).chain())
+        .add_systems(Update, (hud_keyboard, update_hud))
+        .add_systems(
+            Update,
+            (playback_keyboard, playback_buttons, update_playback_text),
+        )
+        .add_systems(Update, update_timeline_bar)
+        .add_systems(
+            Update,
+            (
+                collect_events,
+                log_simulation_failure,
+                scroll_event_log,
+                event_log_filters,
+                update_event_log,
+            )
+                .chain(),
+        )
+        .add_systems(
+            Update,
+            (update_inventory, fade_inventory_highlights).chain(),
+        )
+        .add_systems(
+            Update,
+            (
+                update_weather,
+                spawn_weather_particles,
+                update_weather_particles,
+            ),
+        )
+        .add_systems(Update, (collect_trail, trail_keyboard, draw_trail).chain())
+        .add_systems(Update, (fog_keyboard, update_fog, fade_discoveries).chain())
+        .add_systems(Update, (pick_tile, update_inspector).chain())
+        .add_systems(
+            Update,
+            (
+                minimap_keyboard,
+                minimap_click,
+                update_minimap_image,
+                update_minimap_markers,
+            )
+                .chain(),
+        )
+        .add_systems(
+            Update,
+            (overlay_keyboard, update_overlay, draw_contours).chain(),
+        )
+        .add_systems(Update, (chart_controls, update_charts).chain())
+        .add_systems(Last, shutdown_simulation)
+        .run();
+}
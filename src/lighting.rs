@@ -0,0 +1,216 @@
+use std::fs;
+use std::path::Path;
+
+use bevy::prelude::{Color, Resource};
+use serde::Deserialize;
+
+const MINUTES_PER_DAY: f32 = 24.0 * 60.0;
+
+// used when --lighting isn't given, same format as the files it accepts
+const DEFAULT_GRADIENT: &str = include_str!("../assets/lighting.json");
+
+#[derive(Deserialize)]
+struct KeyframeFile {
+    // "HH:MM"
+    time: String,
+    color: [f32; 3],
+    // how much the color covers the world, 0 leaves it untouched
+    intensity: f32,
+}
+
+#[derive(Clone, Copy, Debug)]
+struct Keyframe {
+    minute: f32,
+    color: [f32; 3],
+    intensity: f32,
+}
+
+// color laid over the world through the day, interpolated between keyframes
+#[derive(Resource, Debug)]
+pub struct LightingGradient {
+    // sorted by minute, never empty
+    keyframes: Vec<Keyframe>,
+}
+
+impl Default for LightingGradient {
+    fn default() -> Self {
+        Self::parse(DEFAULT_GRADIENT).expect("the default lighting gradient is valid")
+    }
+}
+
+impl LightingGradient {
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let text = fs::read_to_string(path)
+            .map_err(|err| format!("could not read '{}': {}", path.display(), err))?;
+        Self::parse(&text).map_err(|err| format!("invalid lighting '{}': {}", path.display(), err))
+    }
+
+    fn parse(text: &str) -> Result<Self, String> {
+        let file: Vec<KeyframeFile> = serde_json::from_str(text).map_err(|err| err.to_string())?;
+        let mut keyframes = Vec::new();
+        for keyframe in file {
+            let minute = parse_time_of_day(&keyframe.time)
+                .ok_or_else(|| format!("'{}' is not a time of day", keyframe.time))?;
+            if !(0.0..=1.0).contains(&keyframe.intensity) {
+                return Err(format!(
+                    "intensity at {} must be between 0 and 1",
+                    keyframe.time
+                ));
+            }
+            if keyframe.color.iter().any(|c| !(0.0..=1.0).contains(c)) {
+                return Err(format!(
+                    "color at {} must have components between 0 and 1",
+                    keyframe.time
+                ));
+            }
+            keyframes.push(Keyframe {
+                minute,
+                color: keyframe.color,
+                intensity: keyframe.intensity,
+            });
+        }
+        if keyframes.is_empty() {
+            return Err(String::from("at least one keyframe is needed"));
+        }
+        keyframes.sort_by(|a, b| a.minute.total_cmp(&b.minute));
+        Ok(Self { keyframes })
+    }
+
+    // overlay color at `minute` after midnight, wrapping around the end of the day
+    pub fn sample(&self, minute: f32) -> Color {
+        let minute = minute.rem_euclid(MINUTES_PER_DAY);
+        let next_index = self
+            .keyframes
+            .iter()
+            .position(|keyframe| keyframe.minute > minute)
+            .unwrap_or(0);
+        let next = self.keyframes[next_index];
+        let previous =
+            self.keyframes[(next_index + self.keyframes.len() - 1) % self.keyframes.len()];
+
+        let span = (next.minute - previous.minute).rem_euclid(MINUTES_PER_DAY);
+        let t = if span > 0.0 {
+            (minute - previous.minute).rem_euclid(MINUTES_PER_DAY) / span
+        } else {
+            0.0
+        };
+        let mix = |a: f32, b: f32| a + (b - a) * t;
+        Color::rgba(
+            mix(previous.color[0], next.color[0]),
+            mix(previous.color[1], next.color[1]),
+            mix(previous.color[2], next.color[2]),
+            mix(previous.intensity, next.intensity),
+        )
+    }
+}
+
+// minutes after midnight of "HH:MM" (seconds and whitespace allowed), None if it isn't a time
+pub fn parse_time_of_day(text: &str) -> Option<f32> {
+    let mut parts = text.trim().split(':');
+    let hours = parts.next()?.trim().parse::<u32>().ok()?;
+    let minutes = match parts.next() {
+        Some(minutes) => minutes.trim().parse::<u32>().ok()?,
+        None => 0,
+    };
+    let seconds = match parts.next() {
+        Some(seconds) => seconds.trim().parse::<u32>().ok()?,
+        None => 0,
+    };
+    if parts.next().is_some() || hours > 24 || minutes >= 60 || seconds >= 60 {
+        return None;
+    }
+    Some(((hours * 60 + minutes) as f32 + seconds as f32 / 60.0).rem_euclid(MINUTES_PER_DAY))
+}
+
+// signed distance in minutes from `from` to `to`, the short way around the clock
+pub fn minutes_between(from: f32, to: f32) -> f32 {
+    let difference = (to - from).rem_euclid(MINUTES_PER_DAY);
+    if difference > MINUTES_PER_DAY / 2.0 {
+        difference - MINUTES_PER_DAY
+    } else {
+        difference
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DAY_AND_NIGHT: &str = r#"[
+        { "time": "12:00", "color": [1.0, 1.0, 1.0], "intensity": 1.0 },
+        { "time": "00:00", "color": [0.0, 0.0, 0.0], "intensity": 0.0 }
+    ]"#;
+
+    fn assert_color(color: Color, expected: [f32; 4]) {
+        let actual = [color.r(), color.g(), color.b(), color.a()];
+        for (a, e) in actual.iter().zip(expected.iter()) {
+            assert!((a - e).abs() < 1e-4, "{:?} != {:?}", actual, expected);
+        }
+    }
+
+    #[test]
+    fn times_of_day() {
+        assert_eq!(parse_time_of_day("06:30"), Some(390.0));
+        assert_eq!(parse_time_of_day(" 6:30:30 "), Some(390.5));
+        assert_eq!(parse_time_of_day("23"), Some(1380.0));
+        // midnight at the end of the day wraps to the start
+        assert_eq!(parse_time_of_day("24:00"), Some(0.0));
+        assert_eq!(parse_time_of_day("25:00"), None);
+        assert_eq!(parse_time_of_day("12:60"), None);
+        assert_eq!(parse_time_of_day("1:2:3:4"), None);
+        assert_eq!(parse_time_of_day("noon"), None);
+        assert_eq!(parse_time_of_day(""), None);
+    }
+
+    #[test]
+    fn minutes_go_the_short_way_around() {
+        assert_eq!(minutes_between(60.0, 120.0), 60.0);
+        assert_eq!(minutes_between(120.0, 60.0), -60.0);
+        assert_eq!(minutes_between(1430.0, 10.0), 20.0);
+        assert_eq!(minutes_between(10.0, 1430.0), -20.0);
+    }
+
+    #[test]
+    fn sample_interpolates_between_keyframes() {
+        let gradient = LightingGradient::parse(DAY_AND_NIGHT).unwrap();
+        assert_color(gradient.sample(0.0), [0.0, 0.0, 0.0, 0.0]);
+        assert_color(gradient.sample(720.0), [1.0, 1.0, 1.0, 1.0]);
+        assert_color(gradient.sample(360.0), [0.5, 0.5, 0.5, 0.5]);
+        // between the last keyframe and the first one of the next day
+        assert_color(gradient.sample(1080.0), [0.5, 0.5, 0.5, 0.5]);
+        assert_color(gradient.sample(1440.0 + 360.0), [0.5, 0.5, 0.5, 0.5]);
+    }
+
+    #[test]
+    fn single_keyframe_is_constant() {
+        let gradient = LightingGradient::parse(
+            r#"[{ "time": "08:00", "color": [0.2, 0.4, 0.6], "intensity": 0.5 }]"#,
+        )
+        .unwrap();
+        assert_color(gradient.sample(0.0), [0.2, 0.4, 0.6, 0.5]);
+        assert_color(gradient.sample(900.0), [0.2, 0.4, 0.6, 0.5]);
+    }
+
+    #[test]
+    fn invalid_gradients() {
+        assert!(LightingGradient::parse("[]").is_err());
+        assert!(LightingGradient::parse("not json").is_err());
+        assert!(LightingGradient::parse(
+            r#"[{ "time": "noon", "color": [0.0, 0.0, 0.0], "intensity": 0.5 }]"#
+        )
+        .is_err());
+        assert!(LightingGradient::parse(
+            r#"[{ "time": "12:00", "color": [0.0, 0.0, 0.0], "intensity": 2.0 }]"#
+        )
+        .is_err());
+        assert!(LightingGradient::parse(
+            r#"[{ "time": "12:00", "color": [0.0, 1.5, 0.0], "intensity": 0.5 }]"#
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn default_gradient_parses() {
+        LightingGradient::default();
+    }
+}
@@ -0,0 +1,283 @@
+use std::fs::File;
+use std::io::{BufReader, BufWriter, ErrorKind, Read, Write};
+use std::path::Path;
+
+use bevy::prelude::Resource;
+use serde::{Deserialize, Serialize};
+
+use crate::snapshot::{
+    apply_changes, map_changes, same_shape, BackpackSnapshot, CellChange, TickSnapshot,
+};
+use crate::world::{Content, EnvironmentalConditions, Event, Tile};
+
+// file starts with this, bump the last byte when the frame layout changes
+const MAGIC: &[u8; 8] = b"VISRPL02";
+
+// one tick in the replay file, the map is only stored in full when its size changes
+#[derive(Serialize, Deserialize)]
+struct ReplayFrame {
+    tick: u64,
+    map: MapRecord,
+    robot_position: (usize, usize),
+    environment: EnvironmentalConditions,
+    energy: usize,
+    max_energy: usize,
+    backpack_size: usize,
+    backpack: Vec<(Content, usize)>,
+    events: Vec<Event>,
+}
+
+#[derive(Serialize, Deserialize)]
+enum MapRecord {
+    Full(Vec<Vec<Option<Tile>>>),
+    // (row, col, tile) of every cell that differs from the previous frame
+    Changes(Vec<CellChange>),
+}
+
+pub struct ReplayWriter {
+    file: BufWriter<File>,
+    previous_map: Vec<Vec<Option<Tile>>>,
+}
+
+impl ReplayWriter {
+    pub fn create(path: &Path) -> Result<Self, String> {
+        let file = File::create(path)
+            .map_err(|err| format!("could not create replay '{}': {}", path.display(), err))?;
+        let mut file = BufWriter::new(file);
+        file.write_all(MAGIC)
+            .map_err(|err| format!("could not write replay: {}", err))?;
+        Ok(Self {
+            file,
+            previous_map: Vec::new(),
+        })
+    }
+
+    pub fn write(&mut self, snapshot: &TickSnapshot) -> Result<(), String> {
+        let map = if same_shape(&self.previous_map, &snapshot.map) {
+            MapRecord::Changes(map_changes(&self.previous_map, &snapshot.map))
+        } else {
+            MapRecord::Full(snapshot.map.clone())
+        };
+        let frame = ReplayFrame {
+            tick: snapshot.tick,
+            map,
+            robot_position: snapshot.robot_position,
+            environment: snapshot.environment.clone(),
+            energy: snapshot.energy,
+            max_energy: snapshot.max_energy,
+            backpack_size: snapshot.backpack.size,
+            backpack: snapshot
+                .backpack
+                .contents
+                .iter()
+                .map(|(content, amount)| (content.clone(), *amount))
+                .collect(),
+            events: snapshot.events.clone(),
+        };
+        bincode::serialize_into(&mut self.file, &frame)
+            .map_err(|err| format!("could not write replay: {}", err))?;
+        self.previous_map = snapshot.map.clone();
+        Ok(())
+    }
+
+    pub fn flush(&mut self) -> Result<(), String> {
+        self.file
+            .flush()
+            .map_err(|err| format!("could not write replay: {}", err))
+    }
+}
+
+pub struct ReplayReader {
+    file: BufReader<File>,
+    map: Vec<Vec<Option<Tile>>>,
+}
+
+impl ReplayReader {
+    pub fn open(path: &Path) -> Result<Self, String> {
+        let file = File::open(path)
+            .map_err(|err| format!("could not open replay '{}': {}", path.display(), err))?;
+        let mut file = BufReader::new(file);
+        let mut magic = [0u8; 8];
+        file.read_exact(&mut magic)
+            .map_err(|_| format!("'{}' is not a replay file", path.display()))?;
+        if &magic != MAGIC {
+            return Err(format!(
+                "'{}' is not a replay file or was written by another version",
+                path.display()
+            ));
+        }
+        Ok(Self {
+            file,
+            map: Vec::new(),
+        })
+    }
+
+    // next tick in the file, None once the file ends
+    pub fn next_snapshot(&mut self) -> Result<Option<TickSnapshot>, String> {
+        let frame: ReplayFrame = match bincode::deserialize_from(&mut self.file) {
+            Ok(frame) => frame,
+            // a run that was killed mid-write just ends early
+            Err(err) if is_end_of_file(&err) => return Ok(None),
+            Err(err) => return Err(format!("corrupted replay: {}", err)),
+        };
+        let changes = match frame.map {
+            MapRecord::Full(map) => {
+                self.map = map;
+                None
+            }
+            MapRecord::Changes(changes) => {
+                apply_changes(&mut self.map, &changes)
+                    .map_err(|err| format!("corrupted replay: {}", err))?;
+                Some(changes)
+            }
+        };
+        Ok(Some(TickSnapshot {
+            tick: frame.tick,
+            map: self.map.clone(),
+            changes,
+            robot_position: frame.robot_position,
+            environment: frame.environment,
+            energy: frame.energy,
+            max_energy: frame.max_energy,
+            backpack: BackpackSnapshot {
+                size: frame.backpack_size,
+                contents: frame.backpack.into_iter().collect(),
+            },
+            events: frame.events,
+        }))
+    }
+}
+
+// writes every snapshot the gui receives when --record is given
+#[derive(Resource)]
+pub struct ReplayRecorder(pub ReplayWriter);
+
+fn is_end_of_file(err: &bincode::Error) -> bool {
+    matches!(&**err, bincode::ErrorKind::Io(io) if io.kind() == ErrorKind::UnexpectedEof)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+    use std::path::PathBuf;
+
+    use super::*;
+    use crate::snapshot::tests::{map, snapshot};
+    use crate::world::WeatherType;
+
+    // a file in the temp directory, removed when dropped
+    struct TempFile(PathBuf);
+
+    impl TempFile {
+        fn new(name: &str) -> Self {
+            Self(std::env::temp_dir().join(format!(
+                "visualizer-{}-{}.replay",
+                std::process::id(),
+                name
+            )))
+        }
+    }
+
+    impl Drop for TempFile {
+        fn drop(&mut self) {
+            let _ = fs::remove_file(&self.0);
+        }
+    }
+
+    fn recorded_run() -> Vec<TickSnapshot> {
+        let mut first = snapshot(1, map(2, 3, 0));
+        first.map[1][2] = None;
+        first.events = vec![Event::Ready];
+
+        let mut second = snapshot(2, first.map.clone());
+        second.map[1][2] = map(1, 1, 4)[0][0].clone();
+        second.robot_position = (1, 2);
+        second.energy = 990;
+        second.max_energy = 2000;
+        second.environment =
+            EnvironmentalConditions::new(String::from("13:00"), WeatherType::Rainy);
+        second.backpack.size = 20;
+        second.backpack.contents.insert(Content::Coin(0), 3);
+        second.events = vec![
+            Event::EnergyConsumed(10),
+            Event::AddedToBackpack(Content::Coin(0), 3),
+        ];
+
+        // the map grew, stored in full again
+        let mut third = snapshot(3, map(3, 3, 1));
+        third.events = vec![Event::Terminated];
+        vec![first, second, third]
+    }
+
+    #[test]
+    fn round_trip() {
+        let file = TempFile::new("round-trip");
+        let run = recorded_run();
+        let mut writer = ReplayWriter::create(&file.0).unwrap();
+        for snapshot in run.iter() {
+            writer.write(snapshot).unwrap();
+        }
+        writer.flush().unwrap();
+        drop(writer);
+
+        let mut reader = ReplayReader::open(&file.0).unwrap();
+        for expected in run.iter() {
+            let read = reader.next_snapshot().unwrap().unwrap();
+            assert_eq!(read.tick, expected.tick);
+            assert_eq!(read.map, expected.map);
+            assert_eq!(read.robot_position, expected.robot_position);
+            assert_eq!(read.environment, expected.environment);
+            assert_eq!(read.energy, expected.energy);
+            assert_eq!(read.max_energy, expected.max_energy);
+            assert_eq!(read.backpack.size, expected.backpack.size);
+            assert_eq!(read.backpack.contents, expected.backpack.contents);
+            assert_eq!(read.events, expected.events);
+        }
+        assert!(reader.next_snapshot().unwrap().is_none());
+    }
+
+    #[test]
+    fn changes_are_read_back() {
+        let file = TempFile::new("changes");
+        let mut writer = ReplayWriter::create(&file.0).unwrap();
+        for snapshot in recorded_run().iter() {
+            writer.write(snapshot).unwrap();
+        }
+        drop(writer);
+
+        let mut reader = ReplayReader::open(&file.0).unwrap();
+        assert_eq!(reader.next_snapshot().unwrap().unwrap().changes, None);
+        let second = reader.next_snapshot().unwrap().unwrap();
+        assert_eq!(second.changes, Some(vec![(1, 2, second.map[1][2].clone())]));
+        // full maps don't say what changed
+        assert_eq!(reader.next_snapshot().unwrap().unwrap().changes, None);
+    }
+
+    #[test]
+    fn truncated_file_ends_early() {
+        let file = TempFile::new("truncated");
+        let mut writer = ReplayWriter::create(&file.0).unwrap();
+        for snapshot in recorded_run().iter() {
+            writer.write(snapshot).unwrap();
+        }
+        drop(writer);
+        let bytes = fs::read(&file.0).unwrap();
+        fs::write(&file.0, &bytes[..bytes.len() - 5]).unwrap();
+
+        let mut reader = ReplayReader::open(&file.0).unwrap();
+        assert_eq!(reader.next_snapshot().unwrap().unwrap().tick, 1);
+        assert_eq!(reader.next_snapshot().unwrap().unwrap().tick, 2);
+        assert!(reader.next_snapshot().unwrap().is_none());
+    }
+
+    #[test]
+    fn other_files_are_refused() {
+        let file = TempFile::new("other");
+        fs::write(&file.0, b"not a replay").unwrap();
+        assert!(ReplayReader::open(&file.0).is_err());
+        fs::write(&file.0, b"VIS").unwrap();
+        assert!(ReplayReader::open(&file.0).is_err());
+        fs::write(&file.0, b"VISRPL01 an older replay").unwrap();
+        assert!(ReplayReader::open(&file.0).is_err());
+    }
+}
@@ -1,18 +1,312 @@
-use bevy::prelude::{Resource, Timer};
-use robotics_lib::runner::Runner;
+use crate::coords::MapGrid;
+use crate::history::SnapshotHistory;
+use bevy::prelude::{Entity, Handle, Image, Resource, Vec2};
+use bevy::sprite::TextureAtlas;
+use rand::rngs::StdRng;
+use std::collections::{BTreeMap, HashMap, VecDeque};
+
+use std::time::Duration;
+
+use crate::world::Tile;
 
 #[derive(Resource)]
 pub struct MapInfo {
-    pub(crate) size: u32,
+    pub(crate) rows: u32,
+    pub(crate) cols: u32,
     pub(crate) last_known_robot_position: (usize, usize),
     pub(crate) current_time: (u8, u8),
 }
 
+impl MapInfo {
+    pub fn grid(&self) -> MapGrid {
+        MapGrid::new(self.rows, self.cols)
+    }
+}
+
+#[derive(Resource)]
+pub struct RandomSource(pub StdRng);
+
+// speed steps reachable with the playback controls
+const SPEED_STEPS: [f32; 9] = [0.25, 0.5, 1.0, 2.0, 4.0, 8.0, 16.0, 32.0, 64.0];
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum PlaybackSpeed {
+    Multiplier(f32),
+    // as fast as possible
+    Max,
+}
+
+impl Default for PlaybackSpeed {
+    fn default() -> Self {
+        PlaybackSpeed::Multiplier(1.0)
+    }
+}
+
+impl PlaybackSpeed {
+    pub fn faster(self) -> Self {
+        match self {
+            PlaybackSpeed::Multiplier(current) => SPEED_STEPS
+                .iter()
+                .find(|step| **step > current)
+                .map(|step| PlaybackSpeed::Multiplier(*step))
+                .unwrap_or(PlaybackSpeed::Max),
+            PlaybackSpeed::Max => PlaybackSpeed::Max,
+        }
+    }
+
+    pub fn slower(self) -> Self {
+        match self {
+            PlaybackSpeed::Multiplier(current) => SPEED_STEPS
+                .iter()
+                .rev()
+                .find(|step| **step < current)
+                .map(|step| PlaybackSpeed::Multiplier(*step))
+                .unwrap_or(self),
+            PlaybackSpeed::Max => PlaybackSpeed::Multiplier(SPEED_STEPS[SPEED_STEPS.len() - 1]),
+        }
+    }
+
+    // time between two ticks given the base tick interval
+    pub fn interval(self, base: Duration) -> Duration {
+        match self {
+            PlaybackSpeed::Multiplier(multiplier) => base.div_f32(multiplier),
+            PlaybackSpeed::Max => Duration::ZERO,
+        }
+    }
+
+    pub fn label(self) -> String {
+        match self {
+            PlaybackSpeed::Multiplier(multiplier) => format!("{}x", multiplier),
+            PlaybackSpeed::Max => String::from("max"),
+        }
+    }
+}
+
 #[derive(Resource)]
-pub struct RunnerTag(pub Runner);
+pub struct PlaybackState {
+    pub(crate) paused: bool,
+    pub(crate) speed: PlaybackSpeed,
+    // seconds between ticks at 1x
+    pub(crate) tick_interval: f32,
+}
+
+impl PlaybackState {
+    pub fn new(tick_interval: f32) -> Self {
+        Self {
+            paused: false,
+            speed: PlaybackSpeed::default(),
+            tick_interval,
+        }
+    }
+}
+
+// which tick of the history is on screen
+#[derive(Resource, Default)]
+pub struct Timeline {
+    // None follows the newest tick
+    pub(crate) viewing: Option<u64>,
+    // seconds since the cursor last moved while playing through the history
+    pub(crate) elapsed: f32,
+}
+
+impl Timeline {
+    pub fn index(&self, history: &SnapshotHistory) -> Option<usize> {
+        match self.viewing {
+            Some(tick) => history.index_of(tick),
+            None => history.len().checked_sub(1),
+        }
+    }
 
-unsafe impl Sync for RunnerTag {}
-unsafe impl Send for RunnerTag {}
+    // move the cursor by `delta` ticks, going past the newest tick goes back to live
+    pub fn step(&mut self, history: &SnapshotHistory, delta: i64) {
+        let Some(index) = self.index(history) else {
+            return;
+        };
+        let target = (index as i64 + delta).max(0) as usize;
+        self.jump_to(history, target);
+    }
+
+    pub fn jump_to(&mut self, history: &SnapshotHistory, index: usize) {
+        self.elapsed = 0.0;
+        if index + 1 >= history.len() {
+            self.viewing = None;
+        } else {
+            self.viewing = history.iter().nth(index).map(|snapshot| snapshot.tick);
+        }
+    }
+}
 
+// contents_robotic_lib.png split into icons for the ui
 #[derive(Resource)]
-pub struct GameTimer(pub Timer);
+pub struct ContentIcons(pub Handle<TextureAtlas>);
+
+// backpack counts last shown and rows still highlighted, keyed by atlas index
+#[derive(Resource, Default)]
+pub struct InventoryHighlights {
+    pub(crate) previous: BTreeMap<u32, (&'static str, usize)>,
+    // seconds left and whether the count went up
+    pub(crate) flashes: HashMap<u32, (f32, bool)>,
+}
+
+// how the robot's path is drawn, t cycles through them
+#[derive(Clone, Copy, PartialEq, Debug, Default)]
+pub enum TrailMode {
+    #[default]
+    Recent,
+    ByAge,
+    ByEnergy,
+    Off,
+}
+
+impl TrailMode {
+    pub fn next(self) -> Self {
+        match self {
+            TrailMode::Recent => TrailMode::ByAge,
+            TrailMode::ByAge => TrailMode::ByEnergy,
+            TrailMode::ByEnergy => TrailMode::Off,
+            TrailMode::Off => TrailMode::Recent,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct TrailPoint {
+    pub(crate) tick: u64,
+    pub(crate) position: (usize, usize),
+    pub(crate) energy: usize,
+}
+
+// every tile the robot moved to in the ticks kept by the history, oldest first
+#[derive(Resource, Default)]
+pub struct RobotTrail {
+    pub(crate) points: VecDeque<TrailPoint>,
+    pub(crate) last_tick: Option<u64>,
+    pub(crate) max_energy: usize,
+    pub(crate) mode: TrailMode,
+}
+
+// what is drawn on tiles the robot hasn't discovered, g switches
+#[derive(Resource, Clone, Copy, PartialEq, Debug, Default)]
+pub enum FogView {
+    #[default]
+    Knowledge,
+    GroundTruth,
+}
+
+// the world as generated before the robot's first tick, only known when running a
+// robot. it is not updated as the robot changes it
+#[derive(Resource, Default)]
+pub struct GroundTruth(pub Option<Vec<Vec<Tile>>>);
+
+impl GroundTruth {
+    // tile at (row, col) when the real world is being shown
+    pub fn tile(&self, view: FogView, row: usize, col: usize) -> Option<&Tile> {
+        if view != FogView::GroundTruth {
+            return None;
+        }
+        self.0.as_ref()?.get(row).and_then(|cells| cells.get(col))
+    }
+}
+
+// how many tiles of the snapshot shown are known, and the ones still highlighted
+#[derive(Resource, Default)]
+pub struct FogState {
+    pub(crate) known: usize,
+    // seconds left of the discovery highlight, by (row, col)
+    pub(crate) discovered: HashMap<(usize, usize), f32>,
+}
+
+// quantity badges over contents, by (row, col), q hides them
+#[derive(Resource)]
+pub struct ContentLabels {
+    pub(crate) entities: HashMap<(usize, usize), Entity>,
+    pub(crate) enabled: bool,
+    // enabled and zoomed in enough to read them
+    pub(crate) visible: bool,
+}
+
+impl Default for ContentLabels {
+    fn default() -> Self {
+        Self {
+            entities: HashMap::new(),
+            enabled: true,
+            visible: true,
+        }
+    }
+}
+
+// tile under the cursor and the one pinned with a click, as (row, col)
+#[derive(Resource, Default)]
+pub struct InspectedTile {
+    pub(crate) hovered: Option<(usize, usize)>,
+    pub(crate) pinned: Option<(usize, usize)>,
+}
+
+// what is drawn over the tiles, e cycles through them
+#[derive(Clone, Copy, PartialEq, Debug, Default)]
+pub enum OverlayMode {
+    #[default]
+    Off,
+    Elevation,
+    Contours,
+    MovementCost,
+    EnergyCost,
+}
+
+impl OverlayMode {
+    pub fn next(self) -> Self {
+        match self {
+            OverlayMode::Off => OverlayMode::Elevation,
+            OverlayMode::Elevation => OverlayMode::Contours,
+            OverlayMode::Contours => OverlayMode::MovementCost,
+            OverlayMode::MovementCost => OverlayMode::EnergyCost,
+            OverlayMode::EnergyCost => OverlayMode::Off,
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            OverlayMode::Off => "off",
+            OverlayMode::Elevation => "elevation",
+            OverlayMode::Contours => "elevation contours",
+            OverlayMode::MovementCost => "movement cost",
+            OverlayMode::EnergyCost => "energy cost",
+        }
+    }
+}
+
+#[derive(Resource, Default)]
+pub struct TerrainOverlay {
+    pub(crate) mode: OverlayMode,
+    // lowest and highest value drawn, colors are spread between them
+    pub(crate) range: Option<(usize, usize)>,
+    // edges between elevation bands, in world space
+    pub(crate) contours: Vec<(Vec2, Vec2)>,
+}
+
+// whether the main camera follows the robot, and the mouse drag in progress
+#[derive(Resource)]
+pub struct CameraControl {
+    pub(crate) follow: bool,
+    // cursor position the drag last moved the camera from
+    pub(crate) drag_from: Option<Vec2>,
+    // the last press of the button moved the camera, so it wasn't a click
+    pub(crate) dragged: bool,
+}
+
+impl Default for CameraControl {
+    fn default() -> Self {
+        Self {
+            follow: true,
+            drag_from: None,
+            dragged: false,
+        }
+    }
+}
+
+// the minimap's texture, one pixel per tile, m hides it
+#[derive(Resource)]
+pub struct Minimap {
+    pub(crate) image: Handle<Image>,
+    pub(crate) visible: bool,
+}
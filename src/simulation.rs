@@ -0,0 +1,257 @@
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+
+use bevy::app::AppExit;
+use bevy::prelude::{EventReader, ResMut, Resource};
+use crossbeam_channel::{Receiver, RecvTimeoutError, Sender};
+
+use crate::cli::Args;
+use crate::replay::ReplayReader;
+use crate::resources::PlaybackSpeed;
+use crate::snapshot::{SnapshotSender, TickSnapshot};
+use crate::world::Tile;
+
+// snapshots the gui may lag behind before the simulation waits for it
+const MAX_PENDING_SNAPSHOTS: usize = 8;
+
+pub enum SimulationCommand {
+    Pause,
+    Resume,
+    // process exactly one tick and stay paused
+    Step,
+    SetSpeed(PlaybackSpeed),
+    Shutdown,
+}
+
+// handle to the thread owning the runner or replay, the runner itself never leaves that thread
+#[derive(Resource)]
+pub struct Simulation {
+    commands: Sender<SimulationCommand>,
+    // why the simulation stopped, when a tick failed
+    failures: Receiver<String>,
+    thread: Option<JoinHandle<()>>,
+    // the world as generated, replays don't have it
+    ground_truth: Option<Vec<Vec<Tile>>>,
+}
+
+// what a tick source did when asked for the next tick
+pub enum TickOutcome {
+    Ticked,
+    // the replay ended or the robot terminated
+    Finished,
+    Failed(String),
+}
+
+// produces the next tick, nothing is asked of it once it finished or failed
+pub type TickSource = Box<dyn FnMut() -> TickOutcome>;
+
+// creates the robot and world and ticks them, given by the runner crate. called
+// on the simulation thread since runners can't be sent between threads, returns
+// the ticks and the world as generated
+pub type RunnerSource = fn(&Args, SnapshotSender) -> Result<(TickSource, Vec<Vec<Tile>>), String>;
+
+impl Simulation {
+    // create bot and world on a new thread and process the first tick,
+    // returns once the first snapshot is in the channel
+    pub fn spawn(
+        args: &Args,
+        runner: RunnerSource,
+    ) -> Result<(Simulation, Receiver<Arc<TickSnapshot>>), String> {
+        let runner_args = args.clone();
+        Self::start(args.tick_interval, move |snapshots| {
+            let (source, ground_truth) = runner(&runner_args, snapshots)?;
+            Ok((source, Some(ground_truth)))
+        })
+    }
+
+    // play a replay file through the same channel, no runner involved
+    pub fn spawn_replay(
+        path: PathBuf,
+        tick_interval: f32,
+    ) -> Result<(Simulation, Receiver<Arc<TickSnapshot>>), String> {
+        Self::start(tick_interval, move |snapshots| {
+            Ok((replay_source(&path, snapshots)?, None))
+        })
+    }
+
+    // the source is built on the simulation thread since runners can't be sent between threads
+    fn start<F>(
+        tick_interval: f32,
+        build_source: F,
+    ) -> Result<(Simulation, Receiver<Arc<TickSnapshot>>), String>
+    where
+        F: FnOnce(
+                Sender<Arc<TickSnapshot>>,
+            ) -> Result<(TickSource, Option<Vec<Vec<Tile>>>), String>
+            + Send
+            + 'static,
+    {
+        let (snapshot_sender, snapshots) = crossbeam_channel::unbounded();
+        let (command_sender, commands) = crossbeam_channel::unbounded();
+        let (ready_sender, ready) = crossbeam_channel::bounded(1);
+        let (failure_sender, failures) = crossbeam_channel::unbounded();
+
+        let interval = Duration::from_secs_f32(tick_interval);
+        let backlog = snapshot_sender.clone();
+
+        let thread = thread::Builder::new()
+            .name(String::from("simulation"))
+            .spawn(move || {
+                let (mut source, ground_truth) = match build_source(snapshot_sender) {
+                    Ok(built) => built,
+                    Err(err) => {
+                        let _ = ready_sender.send(Err(err));
+                        return;
+                    }
+                };
+                // Process first tick so the map is known before startup
+                let first = source();
+                if backlog.is_empty() {
+                    let err = match first {
+                        TickOutcome::Failed(err) => err,
+                        _ => String::from("nothing to play"),
+                    };
+                    let _ = ready_sender.send(Err(err));
+                    return;
+                }
+                let _ = ready_sender.send(Ok(ground_truth));
+                let stopped = has_stopped(first, &failure_sender);
+                run(
+                    &mut source,
+                    stopped,
+                    &commands,
+                    &backlog,
+                    &failure_sender,
+                    interval,
+                );
+            })
+            .map_err(|err| format!("could not start the simulation thread: {}", err))?;
+
+        let mut simulation = Simulation {
+            commands: command_sender,
+            failures,
+            thread: Some(thread),
+            ground_truth: None,
+        };
+        match ready.recv() {
+            Ok(Ok(ground_truth)) => {
+                simulation.ground_truth = ground_truth;
+                Ok((simulation, snapshots))
+            }
+            Ok(Err(err)) => Err(err),
+            Err(_) => Err(String::from("the simulation thread stopped unexpectedly")),
+        }
+    }
+
+    pub fn take_ground_truth(&mut self) -> Option<Vec<Vec<Tile>>> {
+        self.ground_truth.take()
+    }
+
+    pub fn send(&self, command: SimulationCommand) {
+        let _ = self.commands.send(command);
+    }
+
+    // the reason a tick failed, given once
+    pub fn failure(&self) -> Option<String> {
+        self.failures.try_recv().ok()
+    }
+
+    // stop ticking and wait for the thread, safe to call more than once
+    pub fn shutdown(&mut self) {
+        if let Some(thread) = self.thread.take() {
+            self.send(SimulationCommand::Shutdown);
+            let _ = thread.join();
+        }
+    }
+}
+
+impl Drop for Simulation {
+    fn drop(&mut self) {
+        self.shutdown();
+    }
+}
+
+// sends the ticks of a replay file one by one
+pub fn replay_source(path: &Path, snapshots: SnapshotSender) -> Result<TickSource, String> {
+    let mut reader = ReplayReader::open(path)?;
+    Ok(Box::new(move || match reader.next_snapshot() {
+        Ok(Some(snapshot)) => {
+            let _ = snapshots.send(Arc::new(snapshot));
+            TickOutcome::Ticked
+        }
+        Ok(None) => TickOutcome::Finished,
+        Err(err) => TickOutcome::Failed(err),
+    }))
+}
+
+// true once there is nothing left to tick, failures are passed on to the gui
+fn has_stopped(outcome: TickOutcome, failures: &Sender<String>) -> bool {
+    match outcome {
+        TickOutcome::Ticked => false,
+        TickOutcome::Finished => true,
+        TickOutcome::Failed(err) => {
+            let _ = failures.send(err);
+            true
+        }
+    }
+}
+
+// tick loop of the simulation thread
+fn run(
+    source: &mut TickSource,
+    mut stopped: bool,
+    commands: &Receiver<SimulationCommand>,
+    backlog: &Sender<Arc<TickSnapshot>>,
+    failures: &Sender<String>,
+    base_interval: Duration,
+) {
+    let mut paused = false;
+    let mut speed = PlaybackSpeed::default();
+    let mut next_tick = Instant::now() + speed.interval(base_interval);
+    loop {
+        // nothing to wait for while paused except commands
+        let command = if paused || stopped {
+            commands.recv().map_err(|_| RecvTimeoutError::Disconnected)
+        } else {
+            commands.recv_deadline(next_tick)
+        };
+        match command {
+            Ok(SimulationCommand::Shutdown) | Err(RecvTimeoutError::Disconnected) => break,
+            Ok(SimulationCommand::Pause) => paused = true,
+            Ok(SimulationCommand::Resume) => {
+                paused = false;
+                next_tick = Instant::now() + speed.interval(base_interval);
+            }
+            Ok(SimulationCommand::Step) => {
+                paused = true;
+                if !stopped {
+                    stopped = has_stopped(source(), failures);
+                }
+            }
+            Ok(SimulationCommand::SetSpeed(new_speed)) => {
+                speed = new_speed;
+                next_tick = Instant::now() + speed.interval(base_interval);
+            }
+            Err(RecvTimeoutError::Timeout) => {
+                // don't run away from the gui at high speeds
+                if backlog.len() >= MAX_PENDING_SNAPSHOTS {
+                    next_tick = Instant::now() + Duration::from_millis(5);
+                    continue;
+                }
+                // wait for shutdown once the source is done
+                stopped = has_stopped(source(), failures);
+                // a slow tick delays the next one instead of causing a burst
+                next_tick = (next_tick + speed.interval(base_interval)).max(Instant::now());
+            }
+        }
+    }
+}
+
+// the window was closed, stop the runner before the app goes away
+pub fn shutdown_simulation(mut exit: EventReader<AppExit>, mut simulation: ResMut<Simulation>) {
+    if exit.iter().next().is_some() {
+        simulation.shutdown();
+    }
+}
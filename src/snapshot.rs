@@ -0,0 +1,155 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use bevy::prelude::Resource;
+use crossbeam_channel::{Receiver, Sender};
+
+use crate::world::{Content, EnvironmentalConditions, Event, Tile};
+
+// everything the visualizer knows about one tick, never modified after it is sent
+#[derive(Clone, Debug)]
+pub struct TickSnapshot {
+    pub tick: u64,
+    pub map: Vec<Vec<Option<Tile>>>,
+    // cells that differ from the previous tick, None when unknown or the map changed size
+    pub changes: Option<Vec<CellChange>>,
+    pub robot_position: (usize, usize),
+    pub environment: EnvironmentalConditions,
+    pub energy: usize,
+    // as the robotics_lib the robot ran with defines it
+    pub max_energy: usize,
+    pub backpack: BackpackSnapshot,
+    // events handled since the previous snapshot
+    pub events: Vec<Event>,
+}
+
+impl TickSnapshot {
+    // copy of everything except the map, used where maps are stored as changes
+    pub fn without_map(&self) -> TickSnapshot {
+        TickSnapshot {
+            tick: self.tick,
+            map: Vec::new(),
+            changes: None,
+            robot_position: self.robot_position,
+            environment: self.environment.clone(),
+            energy: self.energy,
+            max_energy: self.max_energy,
+            backpack: self.backpack.clone(),
+            events: self.events.clone(),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct BackpackSnapshot {
+    pub size: usize,
+    pub contents: HashMap<Content, usize>,
+}
+
+// sending end, owned by the wrapper or the replay on the simulation thread
+pub type SnapshotSender = Sender<Arc<TickSnapshot>>;
+
+// receiving end of the wrapper's snapshot channel
+#[derive(Resource)]
+pub struct SnapshotReceiver(pub Receiver<Arc<TickSnapshot>>);
+
+// snapshot currently shown on screen
+#[derive(Resource, Default)]
+pub struct CurrentSnapshot(pub Option<Arc<TickSnapshot>>);
+
+// a cell that changed between two maps: (row, col, new tile)
+pub type CellChange = (usize, usize, Option<Tile>);
+
+pub fn same_shape(a: &[Vec<Option<Tile>>], b: &[Vec<Option<Tile>>]) -> bool {
+    a.len() == b.len() && a.iter().zip(b.iter()).all(|(a, b)| a.len() == b.len())
+}
+
+// cells of `current` that differ from `previous`, both maps must have the same shape
+pub fn map_changes(
+    previous: &[Vec<Option<Tile>>],
+    current: &[Vec<Option<Tile>>],
+) -> Vec<CellChange> {
+    let mut changes = Vec::new();
+    for (row, (old_cells, new_cells)) in previous.iter().zip(current.iter()).enumerate() {
+        for (col, (old, new)) in old_cells.iter().zip(new_cells.iter()).enumerate() {
+            if old != new {
+                changes.push((row, col, new.clone()));
+            }
+        }
+    }
+    changes
+}
+
+pub fn apply_changes(map: &mut [Vec<Option<Tile>>], changes: &[CellChange]) -> Result<(), String> {
+    for (row, col, tile) in changes {
+        let cell = map
+            .get_mut(*row)
+            .and_then(|cells| cells.get_mut(*col))
+            .ok_or("change outside of the map")?;
+        *cell = tile.clone();
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+    use crate::world::{TileType, WeatherType};
+
+    // grass everywhere, `elevation` tells maps apart
+    pub fn map(rows: usize, cols: usize, elevation: usize) -> Vec<Vec<Option<Tile>>> {
+        vec![
+            vec![
+                Some(Tile {
+                    tile_type: TileType::Grass,
+                    content: Content::None,
+                    elevation,
+                });
+                cols
+            ];
+            rows
+        ]
+    }
+
+    pub fn snapshot(tick: u64, map: Vec<Vec<Option<Tile>>>) -> TickSnapshot {
+        TickSnapshot {
+            tick,
+            map,
+            changes: None,
+            robot_position: (0, 0),
+            environment: EnvironmentalConditions::new(String::from("12:00"), WeatherType::Sunny),
+            energy: 1000,
+            max_energy: 1000,
+            backpack: BackpackSnapshot::default(),
+            events: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn changes_rebuild_the_map() {
+        let previous = map(3, 4, 0);
+        let mut current = previous.clone();
+        current[1][2] = None;
+        current[2][3] = map(1, 1, 7)[0][0].clone();
+        let changes = map_changes(&previous, &current);
+        assert_eq!(changes.len(), 2);
+        let mut rebuilt = previous.clone();
+        apply_changes(&mut rebuilt, &changes).unwrap();
+        assert_eq!(rebuilt, current);
+        assert!(map_changes(&current, &current).is_empty());
+    }
+
+    #[test]
+    fn changes_outside_the_map_fail() {
+        let mut small = map(2, 2, 0);
+        assert!(apply_changes(&mut small, &[(2, 0, None)]).is_err());
+        assert!(apply_changes(&mut small, &[(0, 2, None)]).is_err());
+    }
+
+    #[test]
+    fn shapes() {
+        assert!(same_shape(&map(2, 3, 0), &map(2, 3, 1)));
+        assert!(!same_shape(&map(2, 3, 0), &map(3, 2, 0)));
+        assert!(!same_shape(&map(2, 3, 0), &[]));
+    }
+}
@@ -0,0 +1,152 @@
+use crate::coords::TILE_PIXEL_SIZE;
+use crate::entities::{BigBrother, VisualizerRobot};
+use crate::resources::{CameraControl, MapInfo};
+
+use bevy::input::mouse::{MouseScrollUnit, MouseWheel};
+use bevy::input::Input;
+use bevy::prelude::{
+    EventReader, KeyCode, MouseButton, Query, Res, ResMut, Time, Transform, Vec2, With, Without,
+};
+use bevy::render::camera::OrthographicProjection;
+use bevy::ui::Interaction;
+use bevy::window::{PrimaryWindow, Window};
+
+const MIN_SCALE: f32 = 0.15;
+// zoomed out far enough to see this many times the whole map
+const MAX_SCALE_MARGIN: f32 = 1.5;
+// zoom change for each line scrolled
+const ZOOM_STEP: f32 = 1.1;
+// pixels scrolled for each line, for touchpads
+const PIXELS_PER_LINE: f32 = 20.0;
+// screen pixels per second panned with the keyboard
+const PAN_SPEED: f32 = 800.0;
+// pixels the mouse has to move with the button down before it counts as a drag
+const DRAG_THRESHOLD: f32 = 4.0;
+
+// f: follow the robot or move freely, c: center on the robot and follow it again,
+// drag or w/a/s/d to pan, mouse wheel or z/x to zoom
+pub fn camera_controls(
+    time: Res<Time>,
+    keyboard_input: Res<Input<KeyCode>>,
+    mouse_input: Res<Input<MouseButton>>,
+    mut wheel: EventReader<MouseWheel>,
+    map_info: Res<MapInfo>,
+    mut control: ResMut<CameraControl>,
+    windows: Query<&Window, With<PrimaryWindow>>,
+    query_ui: Query<&Interaction>,
+    mut query: Query<(&mut Transform, &mut OrthographicProjection), With<BigBrother>>,
+    query_robot: Query<&Transform, (With<VisualizerRobot>, Without<BigBrother>)>,
+) {
+    let (Ok(window), Ok((mut transform, mut projection))) =
+        (windows.get_single(), query.get_single_mut())
+    else {
+        return;
+    };
+    let over_ui = query_ui
+        .iter()
+        .any(|interaction| *interaction != Interaction::None);
+    let cursor = window.cursor_position();
+    let window_size = Vec2::new(window.width(), window.height());
+    let mut position = transform.translation.truncate();
+    let mut scale = projection.scale;
+
+    let toggled = keyboard_input.just_pressed(KeyCode::F);
+    let recentered = keyboard_input.just_pressed(KeyCode::C);
+    if toggled {
+        control.follow = !control.follow;
+    }
+    if recentered {
+        control.follow = true;
+    }
+    // jump back to the robot instead of waiting for it to move
+    if (toggled || recentered) && control.follow {
+        if let Ok(robot) = query_robot.get_single() {
+            position = robot.translation.truncate();
+        }
+    }
+
+    // keyboard panning, screen speed is the same at every zoom
+    let mut direction = Vec2::ZERO;
+    if keyboard_input.pressed(KeyCode::A) {
+        direction.x -= 1.0;
+    }
+    if keyboard_input.pressed(KeyCode::D) {
+        direction.x += 1.0;
+    }
+    if keyboard_input.pressed(KeyCode::W) {
+        direction.y += 1.0;
+    }
+    if keyboard_input.pressed(KeyCode::S) {
+        direction.y -= 1.0;
+    }
+    if direction != Vec2::ZERO {
+        control.follow = false;
+        position += direction.normalize() * PAN_SPEED * scale * time.delta_seconds();
+    }
+
+    // dragging, the world point under the cursor stays under it
+    if mouse_input.just_pressed(MouseButton::Left) && !over_ui {
+        control.drag_from = cursor;
+        control.dragged = false;
+    }
+    if !mouse_input.pressed(MouseButton::Left) {
+        control.drag_from = None;
+    }
+    if let (Some(from), Some(cursor)) = (control.drag_from, cursor) {
+        let moved = cursor - from;
+        if control.dragged || moved.length() >= DRAG_THRESHOLD {
+            control.dragged = true;
+            control.follow = false;
+            // screen y goes down, world y goes up
+            position -= Vec2::new(moved.x, -moved.y) * scale;
+            control.drag_from = Some(cursor);
+        }
+    }
+
+    // zooming, centered on the cursor unless following the robot
+    let mut lines = 0.0;
+    for event in wheel.iter() {
+        if over_ui {
+            continue;
+        }
+        lines += match event.unit {
+            MouseScrollUnit::Line => event.y,
+            MouseScrollUnit::Pixel => event.y / PIXELS_PER_LINE,
+        };
+    }
+    if keyboard_input.pressed(KeyCode::Z) {
+        lines -= 10.0 * time.delta_seconds();
+    }
+    if keyboard_input.pressed(KeyCode::X) {
+        lines += 10.0 * time.delta_seconds();
+    }
+    let grid = map_info.grid();
+    let max_scale =
+        ((grid.pixel_size() / window_size).max_element() * MAX_SCALE_MARGIN).max(MIN_SCALE);
+    let new_scale = (scale * ZOOM_STEP.powf(-lines)).clamp(MIN_SCALE, max_scale);
+    if new_scale != scale {
+        if let (Some(cursor), false) = (cursor, control.follow) {
+            let offset = Vec2::new(
+                cursor.x - window_size.x / 2.0,
+                window_size.y / 2.0 - cursor.y,
+            );
+            let under_cursor = position + offset * scale;
+            position = under_cursor - offset * new_scale;
+        }
+        scale = new_scale;
+    }
+
+    // the center of the screen never leaves the map
+    let half_tile = Vec2::splat(TILE_PIXEL_SIZE / 2.0);
+    let min = -half_tile;
+    let max = (grid.pixel_size() - half_tile).max(min);
+    position = position.clamp(min, max);
+
+    if position != transform.translation.truncate() {
+        transform.translation.x = position.x;
+        transform.translation.y = position.y;
+    }
+    if scale != projection.scale {
+        projection.scale = scale;
+    }
+}
@@ -0,0 +1,357 @@
+use crate::charts::{ChartData, ChartKind, ChartPoint};
+use crate::cli::{Args, MinimapCorner};
+use crate::components::{ChartImage, ChartLabel};
+use crate::entities::{ChartPanel, ChartTimeText};
+use crate::snapshot::CurrentSnapshot;
+use crate::systems::minimap::minimap_room;
+
+use bevy::asset::Assets;
+use bevy::input::mouse::{MouseScrollUnit, MouseWheel};
+use bevy::input::Input;
+use bevy::prelude::{
+    default, BuildChildren, Color, Commands, DetectChanges, EventReader, Image, KeyCode,
+    NodeBundle, Query, Res, ResMut, Visibility, With, Without,
+};
+use bevy::render::render_resource::{Extent3d, TextureDimension, TextureFormat};
+use bevy::text::{Text, TextSection, TextStyle};
+use bevy::ui::node_bundles::{ImageBundle, TextBundle};
+use bevy::ui::{FlexDirection, Interaction, PositionType, Style, UiImage, UiRect, Val};
+
+// size of each chart in pixels
+const CHART_WIDTH: u32 = 360;
+const CHART_HEIGHT: u32 = 72;
+const FONT_SIZE: f32 = 16.0;
+const BACKGROUND: [u8; 4] = [20, 20, 24, 200];
+const GUIDE: [u8; 4] = [70, 70, 80, 255];
+// one per backpack item, repeating when there are more
+const ITEM_COLORS: [[u8; 4]; 8] = [
+    [230, 90, 80, 255],
+    [90, 180, 240, 255],
+    [120, 210, 100, 255],
+    [240, 200, 70, 255],
+    [190, 120, 230, 255],
+    [240, 150, 60, 255],
+    [100, 220, 200, 255],
+    [220, 220, 220, 255],
+];
+const ENERGY_COLOR: [u8; 4] = [80, 140, 255, 255];
+const COLLECTED_COLOR: [u8; 4] = [255, 210, 60, 255];
+const DEPOSITED_COLOR: [u8; 4] = [110, 220, 110, 255];
+const DISCOVERED_COLOR: [u8; 4] = [200, 200, 210, 255];
+
+pub fn setup_charts(mut commands: Commands, args: Res<Args>, mut images: ResMut<Assets<Image>>) {
+    let mut children = Vec::new();
+    for kind in ChartKind::ALL {
+        let label = commands
+            .spawn((
+                TextBundle::from_section(
+                    "",
+                    TextStyle {
+                        font_size: FONT_SIZE,
+                        color: Color::WHITE,
+                        ..default()
+                    },
+                ),
+                ChartLabel(kind),
+            ))
+            .id();
+        let image = images.add(Image::new_fill(
+            Extent3d {
+                width: CHART_WIDTH,
+                height: CHART_HEIGHT,
+                depth_or_array_layers: 1,
+            },
+            TextureDimension::D2,
+            &BACKGROUND,
+            TextureFormat::Rgba8UnormSrgb,
+        ));
+        let chart = commands
+            .spawn((
+                ImageBundle {
+                    style: Style {
+                        width: Val::Px(CHART_WIDTH as f32),
+                        height: Val::Px(CHART_HEIGHT as f32),
+                        ..default()
+                    },
+                    image: UiImage::new(image),
+                    ..default()
+                },
+                ChartImage(kind),
+            ))
+            .id();
+        children.extend([label, chart]);
+    }
+    let time = commands
+        .spawn((
+            TextBundle::from_section(
+                "",
+                TextStyle {
+                    font_size: FONT_SIZE,
+                    color: Color::GRAY,
+                    ..default()
+                },
+            ),
+            ChartTimeText,
+        ))
+        .id();
+    children.push(time);
+
+    commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    position_type: PositionType::Absolute,
+                    // right of the event log
+                    top: Val::Px(10. + minimap_room(&args, MinimapCorner::TopLeft)),
+                    left: Val::Px(440.),
+                    flex_direction: FlexDirection::Column,
+                    row_gap: Val::Px(2.),
+                    padding: UiRect::all(Val::Px(6.)),
+                    ..default()
+                },
+                background_color: Color::rgba(0.0, 0.0, 0.0, 0.6).into(),
+                visibility: Visibility::Hidden,
+                ..default()
+            },
+            Interaction::default(),
+            ChartPanel,
+        ))
+        .push_children(&children);
+}
+
+// p: show or hide the charts, [ and ] or the mouse wheel over them zoom the time axis
+pub fn chart_controls(
+    keyboard_input: Res<Input<KeyCode>>,
+    mut wheel: EventReader<MouseWheel>,
+    mut charts: ResMut<ChartData>,
+    mut query: Query<(&mut Visibility, &Interaction), With<ChartPanel>>,
+) {
+    let hovered = query
+        .iter()
+        .any(|(_, interaction)| *interaction != Interaction::None);
+    let mut steps = 0;
+    for event in wheel.iter() {
+        if !hovered || event.y == 0.0 {
+            continue;
+        }
+        // scrolling up zooms in
+        let lines = match event.unit {
+            MouseScrollUnit::Line => event.y,
+            MouseScrollUnit::Pixel => event.y / FONT_SIZE,
+        };
+        steps -= lines.signum() as i32;
+    }
+    if keyboard_input.just_pressed(KeyCode::BracketLeft) {
+        steps -= 1;
+    }
+    if keyboard_input.just_pressed(KeyCode::BracketRight) {
+        steps += 1;
+    }
+    if steps != 0 {
+        charts.zoom(steps);
+    }
+
+    if keyboard_input.just_pressed(KeyCode::P) {
+        charts.visible = !charts.visible;
+        for (mut visibility, _) in query.iter_mut() {
+            *visibility = if charts.visible {
+                Visibility::Inherited
+            } else {
+                Visibility::Hidden
+            };
+        }
+    }
+}
+
+// redraw the charts up to the tick on screen
+pub fn update_charts(
+    charts: Res<ChartData>,
+    current: Res<CurrentSnapshot>,
+    mut images: ResMut<Assets<Image>>,
+    query_chart: Query<(&UiImage, &ChartImage)>,
+    mut query_label: Query<(&mut Text, &ChartLabel)>,
+    mut query_time: Query<&mut Text, (With<ChartTimeText>, Without<ChartLabel>)>,
+) {
+    if !charts.visible || !(charts.is_changed() || current.is_changed()) {
+        return;
+    }
+    let Some(tick) = current.0.as_ref().map(|snapshot| snapshot.tick) else {
+        return;
+    };
+    let points = charts.window_ending_at(tick);
+    let (Some(first), Some(last)) = (points.first(), points.last()) else {
+        return;
+    };
+    // the time axis spans the whole window, or everything kept when zoomed out
+    let end = tick;
+    let start = match charts.window_ticks() {
+        0 => first.tick,
+        ticks => end.saturating_sub(ticks),
+    };
+
+    for (image, chart) in query_chart.iter() {
+        let Some(image) = images.get_mut(&image.texture) else {
+            continue;
+        };
+        let series = series(chart.0, &charts, &points);
+        let max = series
+            .iter()
+            .flat_map(|(_, values)| values.iter().copied())
+            .fold(chart_floor(chart.0, last), f32::max);
+        let mut plot = Plot::new(image, start, end, max);
+        plot.clear();
+        for (color, values) in series.iter() {
+            plot.line(&points, values, *color);
+        }
+    }
+
+    for (mut text, label) in query_label.iter_mut() {
+        text.sections = label_sections(label.0, &charts, last);
+    }
+    for mut text in query_time.iter_mut() {
+        text.sections[0].value = format!("ticks {} to {}, [ and ] zoom", start, end);
+    }
+}
+
+// colors and values of the lines on a chart, one value per point
+fn series(kind: ChartKind, charts: &ChartData, points: &[&ChartPoint]) -> Vec<([u8; 4], Vec<f32>)> {
+    let values =
+        |value: fn(&ChartPoint) -> usize| points.iter().map(|point| value(point) as f32).collect();
+    match kind {
+        ChartKind::Energy => vec![(ENERGY_COLOR, values(|point| point.energy))],
+        ChartKind::Backpack => charts
+            .items
+            .iter()
+            .enumerate()
+            .map(|(index, item)| {
+                let counts = points
+                    .iter()
+                    .map(|point| point.backpack.get(item).copied().unwrap_or(0) as f32)
+                    .collect();
+                (ITEM_COLORS[index % ITEM_COLORS.len()], counts)
+            })
+            .collect(),
+        ChartKind::Coins => vec![
+            (COLLECTED_COLOR, values(|point| point.coins_collected)),
+            (DEPOSITED_COLOR, values(|point| point.coins_deposited)),
+        ],
+        ChartKind::Discovered => vec![(DISCOVERED_COLOR, values(|point| point.discovered))],
+    }
+}
+
+// lowest top of the y axis, so flat lines don't fill the whole chart
+fn chart_floor(kind: ChartKind, last: &ChartPoint) -> f32 {
+    match kind {
+        ChartKind::Energy => last.max_energy as f32,
+        _ => 1.0,
+    }
+}
+
+// title and the values at the last tick, colored like their lines
+fn label_sections(kind: ChartKind, charts: &ChartData, last: &ChartPoint) -> Vec<TextSection> {
+    let section = |value: String, color: [u8; 4]| {
+        TextSection::new(
+            value,
+            TextStyle {
+                font_size: FONT_SIZE,
+                color: Color::rgba_u8(color[0], color[1], color[2], color[3]),
+                ..default()
+            },
+        )
+    };
+    let white = [255, 255, 255, 255];
+    match kind {
+        ChartKind::Energy => vec![
+            section(String::from("energy "), white),
+            section(last.energy.to_string(), ENERGY_COLOR),
+        ],
+        ChartKind::Backpack => {
+            let mut sections = vec![section(String::from("backpack"), white)];
+            for (index, item) in charts.items.iter().enumerate() {
+                let count = last.backpack.get(item).copied().unwrap_or(0);
+                sections.push(section(
+                    format!("  {} {}", item, count),
+                    ITEM_COLORS[index % ITEM_COLORS.len()],
+                ));
+            }
+            sections
+        }
+        ChartKind::Coins => vec![
+            section(String::from("coins collected "), white),
+            section(last.coins_collected.to_string(), COLLECTED_COLOR),
+            section(String::from("  deposited "), white),
+            section(last.coins_deposited.to_string(), DEPOSITED_COLOR),
+        ],
+        ChartKind::Discovered => vec![
+            section(String::from("tiles discovered "), white),
+            section(last.discovered.to_string(), DISCOVERED_COLOR),
+        ],
+    }
+}
+
+// draws straight into the pixels of a chart image
+struct Plot<'a> {
+    image: &'a mut Image,
+    start: u64,
+    end: u64,
+    max: f32,
+}
+
+impl<'a> Plot<'a> {
+    fn new(image: &'a mut Image, start: u64, end: u64, max: f32) -> Self {
+        Self {
+            image,
+            start,
+            end,
+            max,
+        }
+    }
+
+    fn clear(&mut self) {
+        for pixel in self.image.data.chunks_exact_mut(4) {
+            pixel.copy_from_slice(&BACKGROUND);
+        }
+        // halfway guide
+        let middle = CHART_HEIGHT as i64 / 2;
+        for x in 0..CHART_WIDTH as i64 {
+            self.set(x, middle, GUIDE);
+        }
+    }
+
+    fn position(&self, tick: u64, value: f32) -> (i64, i64) {
+        let span = (self.end - self.start).max(1) as f32;
+        let x = (tick.saturating_sub(self.start)) as f32 / span * (CHART_WIDTH - 1) as f32;
+        let y = (1.0 - value / self.max.max(f32::EPSILON)) * (CHART_HEIGHT - 1) as f32;
+        (x.round() as i64, y.round() as i64)
+    }
+
+    fn line(&mut self, points: &[&ChartPoint], values: &[f32], color: [u8; 4]) {
+        let positions: Vec<(i64, i64)> = points
+            .iter()
+            .zip(values.iter())
+            .map(|(point, value)| self.position(point.tick, *value))
+            .collect();
+        if let [only] = positions.as_slice() {
+            self.set(only.0, only.1, color);
+        }
+        for pair in positions.windows(2) {
+            let (from, to) = (pair[0], pair[1]);
+            let steps = (to.0 - from.0).abs().max((to.1 - from.1).abs()).max(1);
+            for step in 0..=steps {
+                let x = from.0 + (to.0 - from.0) * step / steps;
+                let y = from.1 + (to.1 - from.1) * step / steps;
+                self.set(x, y, color);
+            }
+        }
+    }
+
+    fn set(&mut self, x: i64, y: i64, color: [u8; 4]) {
+        if x < 0 || y < 0 || x >= CHART_WIDTH as i64 || y >= CHART_HEIGHT as i64 {
+            return;
+        }
+        let start = (y as usize * CHART_WIDTH as usize + x as usize) * 4;
+        if let Some(pixel) = self.image.data.get_mut(start..start + 4) {
+            pixel.copy_from_slice(&color);
+        }
+    }
+}
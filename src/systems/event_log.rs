@@ -0,0 +1,240 @@
+use crate::cli::{Args, MinimapCorner};
+use crate::components::EventLogFilter;
+use crate::entities::{EventLogPanel, EventLogText};
+use crate::event_log::{EventKind, EventLog};
+use crate::history::SnapshotHistory;
+use crate::simulation::Simulation;
+use crate::snapshot::{CurrentSnapshot, SnapshotReceiver, TickSnapshot};
+use crate::systems::minimap::minimap_room;
+
+use bevy::input::mouse::{MouseScrollUnit, MouseWheel};
+use bevy::input::Input;
+use bevy::prelude::{
+    default, BuildChildren, ButtonBundle, Changed, Children, Color, Commands, DetectChanges,
+    DetectChangesMut, EventReader, KeyCode, Local, NodeBundle, Query, Res, ResMut, With, Without,
+};
+use bevy::text::{Text, TextSection, TextStyle};
+use bevy::ui::node_bundles::TextBundle;
+use bevy::ui::{FlexDirection, FlexWrap, Interaction, PositionType, Style, UiRect, Val};
+
+// lines of the log shown at once
+const VISIBLE_LINES: usize = 16;
+const FONT_SIZE: f32 = 16.0;
+
+pub fn setup_event_log(mut commands: Commands, args: Res<Args>) {
+    let panel = commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    position_type: PositionType::Absolute,
+                    // below the minimap when it is in the same corner
+                    top: Val::Px(10. + minimap_room(&args, MinimapCorner::TopLeft)),
+                    left: Val::Px(10.),
+                    width: Val::Px(420.),
+                    flex_direction: FlexDirection::Column,
+                    padding: UiRect::all(Val::Px(6.)),
+                    row_gap: Val::Px(6.),
+                    ..default()
+                },
+                background_color: Color::rgba(0.0, 0.0, 0.0, 0.6).into(),
+                ..default()
+            },
+            // hovered state is used to scroll with the mouse wheel
+            Interaction::default(),
+            EventLogPanel,
+        ))
+        .id();
+    let filters = commands
+        .spawn(NodeBundle {
+            style: Style {
+                flex_direction: FlexDirection::Row,
+                flex_wrap: FlexWrap::Wrap,
+                row_gap: Val::Px(4.),
+                column_gap: Val::Px(4.),
+                ..default()
+            },
+            ..default()
+        })
+        .id();
+    for kind in EventKind::ALL {
+        let button = commands
+            .spawn((
+                ButtonBundle {
+                    style: Style {
+                        padding: UiRect::axes(Val::Px(6.), Val::Px(2.)),
+                        ..default()
+                    },
+                    background_color: Color::rgba(0.1, 0.1, 0.1, 0.8).into(),
+                    ..default()
+                },
+                EventLogFilter(kind),
+            ))
+            .with_children(|parent| {
+                parent.spawn(TextBundle::from_section(
+                    kind.label(),
+                    TextStyle {
+                        font_size: FONT_SIZE,
+                        color: kind.color(),
+                        ..default()
+                    },
+                ));
+            })
+            .id();
+        commands.entity(filters).add_child(button);
+    }
+    let text = commands
+        .spawn((
+            TextBundle {
+                text: Text::from_section(
+                    "",
+                    TextStyle {
+                        font_size: FONT_SIZE,
+                        color: Color::WHITE,
+                        ..default()
+                    },
+                ),
+                ..default()
+            },
+            EventLogText,
+        ))
+        .id();
+    commands.entity(panel).push_children(&[filters, text]);
+}
+
+// take the events of every tick added to the history since last frame
+pub fn collect_events(history: Res<SnapshotHistory>, mut log: ResMut<EventLog>) {
+    if !history.is_changed() {
+        return;
+    }
+    let last_tick = log.last_tick();
+    let new: Vec<&TickSnapshot> = history
+        .iter()
+        .rev()
+        .take_while(|snapshot| last_tick.is_none_or(|last| snapshot.tick > last))
+        .collect();
+    for snapshot in new.into_iter().rev() {
+        log.push(snapshot);
+    }
+}
+
+// a failed tick stops the simulation, logged once the ticks sent before it are in the log
+pub fn log_simulation_failure(
+    simulation: Res<Simulation>,
+    receiver: Res<SnapshotReceiver>,
+    mut failure: Local<Option<String>>,
+    mut log: ResMut<EventLog>,
+) {
+    if let Some(err) = simulation.failure() {
+        *failure = Some(err);
+    }
+    if !receiver.0.is_empty() {
+        return;
+    }
+    if let Some(err) = failure.take() {
+        log.note(format!("stopped, {}", err));
+    }
+}
+
+// mouse wheel over the panel or page up/down scroll through older events
+pub fn scroll_event_log(
+    keyboard_input: Res<Input<KeyCode>>,
+    mut wheel: EventReader<MouseWheel>,
+    panel: Query<&Interaction, With<EventLogPanel>>,
+    mut log: ResMut<EventLog>,
+) {
+    let hovered = panel
+        .iter()
+        .any(|interaction| *interaction != Interaction::None);
+    let mut lines: i64 = 0;
+    for event in wheel.iter() {
+        if !hovered {
+            continue;
+        }
+        lines += match event.unit {
+            MouseScrollUnit::Line => event.y.round() as i64,
+            MouseScrollUnit::Pixel => (event.y / FONT_SIZE).round() as i64,
+        };
+    }
+    if keyboard_input.just_pressed(KeyCode::PageUp) {
+        lines += VISIBLE_LINES as i64;
+    } else if keyboard_input.just_pressed(KeyCode::PageDown) {
+        lines -= VISIBLE_LINES as i64;
+    }
+    if lines != 0 {
+        log.scroll = (log.scroll as i64 + lines).max(0) as usize;
+    }
+}
+
+pub fn event_log_filters(
+    query: Query<(&Interaction, &EventLogFilter), Changed<Interaction>>,
+    mut log: ResMut<EventLog>,
+) {
+    for (interaction, filter) in query.iter() {
+        if *interaction == Interaction::Pressed {
+            log.toggle(filter.0);
+        }
+    }
+}
+
+pub fn update_event_log(
+    mut log: ResMut<EventLog>,
+    current: Res<CurrentSnapshot>,
+    mut query_text: Query<&mut Text, With<EventLogText>>,
+    query_filters: Query<(&EventLogFilter, &Children)>,
+    mut query_labels: Query<&mut Text, Without<EventLogText>>,
+) {
+    if !log.is_changed() && !current.is_changed() {
+        return;
+    }
+    // events after the tick on screen are hidden while looking at the history
+    let Some(tick) = current.0.as_ref().map(|snapshot| snapshot.tick) else {
+        return;
+    };
+    let max_scroll = log.visible(tick).count().saturating_sub(VISIBLE_LINES);
+    if log.scroll > max_scroll {
+        log.bypass_change_detection().scroll = max_scroll;
+    }
+    let mut lines: Vec<TextSection> = log
+        .visible(tick)
+        .skip(log.scroll)
+        .take(VISIBLE_LINES)
+        .map(|entry| {
+            TextSection::new(
+                format!("{:>6}  {}\n", entry.tick, entry.text),
+                TextStyle {
+                    font_size: FONT_SIZE,
+                    color: entry.kind.color(),
+                    ..default()
+                },
+            )
+        })
+        .collect();
+    lines.reverse();
+    if lines.is_empty() {
+        lines.push(TextSection::new(
+            "no events",
+            TextStyle {
+                font_size: FONT_SIZE,
+                color: Color::GRAY,
+                ..default()
+            },
+        ));
+    }
+    for mut text in query_text.iter_mut() {
+        text.sections = lines.clone();
+    }
+
+    // dim the filters that are turned off
+    for (filter, children) in query_filters.iter() {
+        let color = if log.hidden.contains(&filter.0) {
+            Color::DARK_GRAY
+        } else {
+            filter.0.color()
+        };
+        for child in children.iter() {
+            if let Ok(mut label) = query_labels.get_mut(*child) {
+                label.sections[0].style.color = color;
+            }
+        }
+    }
+}
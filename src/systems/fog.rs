@@ -0,0 +1,165 @@
+use crate::entities::{DiscoveryText, FogLayer, FogMap};
+use crate::events::MapDiffEvent;
+use crate::resources::{FogState, FogView, GroundTruth, MapInfo};
+use crate::snapshot::CurrentSnapshot;
+use crate::systems::changed_cells;
+use crate::tiles::{DISCOVERED_INDEX, FOG_INDEX};
+
+use bevy::input::Input;
+use bevy::prelude::{
+    default, Color, Commands, DetectChanges, EventReader, KeyCode, Query, Res, ResMut, Time, With,
+};
+use bevy::text::{Text, TextStyle};
+use bevy::ui::node_bundles::TextBundle;
+use bevy::ui::{PositionType, Style, Val};
+
+use bevy_ecs_tilemap::prelude::{TileColor, TileStorage, TileTextureIndex, TileVisible};
+
+// seconds a newly discovered tile stays outlined
+const DISCOVERY_SECONDS: f32 = 1.0;
+// fog left over undiscovered tiles while the real world is shown
+const GROUND_TRUTH_FOG_ALPHA: f32 = 0.45;
+
+pub fn setup_discovery_text(mut commands: Commands) {
+    commands.spawn((
+        TextBundle {
+            text: Text::from_section(
+                "",
+                TextStyle {
+                    font_size: 20.0,
+                    color: Color::WHITE,
+                    ..default()
+                },
+            ),
+            style: Style {
+                position_type: PositionType::Absolute,
+                // under the weather
+                top: Val::Px(56.),
+                left: Val::Percent(42.),
+                ..default()
+            },
+            ..default()
+        },
+        DiscoveryText,
+    ));
+}
+
+// g: show the generated world under the fog, when running a robot
+pub fn fog_keyboard(
+    keyboard_input: Res<Input<KeyCode>>,
+    ground_truth: Res<GroundTruth>,
+    mut fog_view: ResMut<FogView>,
+) {
+    if !keyboard_input.just_pressed(KeyCode::G) || ground_truth.0.is_none() {
+        return;
+    }
+    *fog_view = match *fog_view {
+        FogView::Knowledge => FogView::GroundTruth,
+        FogView::GroundTruth => FogView::Knowledge,
+    };
+}
+
+pub fn update_fog(
+    mut events: EventReader<MapDiffEvent>,
+    current: Res<CurrentSnapshot>,
+    fog_view: Res<FogView>,
+    map_info: Res<MapInfo>,
+    mut fog: ResMut<FogState>,
+    query_layer: Query<&TileStorage, With<FogLayer>>,
+    mut query: Query<(&mut TileTextureIndex, &mut TileVisible, &mut TileColor), With<FogMap>>,
+    mut query_text: Query<&mut Text, With<DiscoveryText>>,
+) {
+    let (Some(snapshot), Ok(storage)) = (current.0.as_ref(), query_layer.get_single()) else {
+        return;
+    };
+    let Some((cells, all)) = changed_cells(&mut events, fog_view.is_changed(), &snapshot.map)
+    else {
+        return;
+    };
+    if all {
+        fog.known = 0;
+    }
+
+    let grid = map_info.grid();
+    let fog_alpha = match *fog_view {
+        FogView::Knowledge => 1.0,
+        FogView::GroundTruth => GROUND_TRUTH_FOG_ALPHA,
+    };
+    for (row, col) in cells {
+        let Some(entity) = grid.tile_pos(row, col).and_then(|pos| storage.get(&pos)) else {
+            continue;
+        };
+        let Ok((mut index, mut visible, mut color)) = query.get_mut(entity) else {
+            continue;
+        };
+        // the fog tile itself remembers whether the cell was known before
+        let was_known = !(visible.0 && index.0 == FOG_INDEX);
+        let is_known = snapshot.map[row][col].is_some();
+        if all {
+            fog.known += is_known as usize;
+        } else if is_known != was_known {
+            fog.known = if is_known {
+                fog.known + 1
+            } else {
+                fog.known.saturating_sub(1)
+            };
+        }
+
+        if !is_known {
+            fog.discovered.remove(&(row, col));
+            index.0 = FOG_INDEX;
+            visible.0 = true;
+            color.0 = Color::rgba(1.0, 1.0, 1.0, fog_alpha);
+        } else if !was_known && !all {
+            // outline what was found since the last snapshot shown
+            fog.discovered.insert((row, col), DISCOVERY_SECONDS);
+            index.0 = DISCOVERED_INDEX;
+            visible.0 = true;
+            color.0 = Color::WHITE;
+        } else if !fog.discovered.contains_key(&(row, col)) {
+            visible.0 = false;
+        }
+    }
+
+    let total: usize = snapshot.map.iter().map(|row| row.len()).sum();
+    let percentage = if total > 0 {
+        100.0 * fog.known as f32 / total as f32
+    } else {
+        0.0
+    };
+    for mut text in query_text.iter_mut() {
+        text.sections[0].value = match *fog_view {
+            FogView::Knowledge => format!("discovered {:.1}%", percentage),
+            FogView::GroundTruth => format!("discovered {:.1}%  (initial world)", percentage),
+        };
+    }
+}
+
+// fade the outline of newly discovered tiles
+pub fn fade_discoveries(
+    time: Res<Time>,
+    map_info: Res<MapInfo>,
+    mut fog: ResMut<FogState>,
+    query_layer: Query<&TileStorage, With<FogLayer>>,
+    mut query: Query<(&mut TileVisible, &mut TileColor), With<FogMap>>,
+) {
+    let Ok(storage) = query_layer.get_single() else {
+        return;
+    };
+    let grid = map_info.grid();
+    let delta = time.delta_seconds();
+    for ((row, col), remaining) in fog.discovered.iter_mut() {
+        *remaining -= delta;
+        let Some(entity) = grid.tile_pos(*row, *col).and_then(|pos| storage.get(&pos)) else {
+            continue;
+        };
+        if let Ok((mut visible, mut color)) = query.get_mut(entity) {
+            if *remaining > 0.0 {
+                color.0 = Color::rgba(1.0, 1.0, 1.0, *remaining / DISCOVERY_SECONDS);
+            } else {
+                visible.0 = false;
+            }
+        }
+    }
+    fog.discovered.retain(|_, remaining| *remaining > 0.0);
+}
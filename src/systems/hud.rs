@@ -0,0 +1,256 @@
+use crate::cli::{Args, HudPanel, MinimapCorner};
+use crate::components::{HudSection, HudText};
+use crate::entities::Hud;
+use crate::snapshot::CurrentSnapshot;
+use crate::systems::minimap::minimap_room;
+use crate::tiles::{tile_name, weather_name};
+
+use bevy::diagnostic::{DiagnosticsStore, FrameTimeDiagnosticsPlugin};
+use bevy::input::Input;
+use bevy::prelude::{
+    default, BuildChildren, Color, Commands, DetectChanges, KeyCode, NodeBundle, Query, Res, With,
+    Without,
+};
+use bevy::text::{Text, TextSection, TextStyle};
+use bevy::ui::node_bundles::TextBundle;
+use bevy::ui::{
+    AlignContent, AlignItems, AlignSelf, Display, FlexDirection, JustifyContent, PositionType,
+    Style, UiRect, Val, ZIndex,
+};
+
+pub fn setup_hud(mut commands: Commands, args: Res<Args>) {
+    let display = |panel: HudPanel| {
+        if args.hud.contains(&panel) {
+            Display::Flex
+        } else {
+            Display::None
+        }
+    };
+    let energy_bar_layout = commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    display: display(HudPanel::Energy),
+                    width: Val::Vw(95.0),
+                    height: Val::Vh(5.),
+                    border: UiRect::all(Val::Px(2.)),
+                    flex_direction: FlexDirection::Column,
+                    align_self: AlignSelf::FlexEnd,
+                    ..default()
+                },
+
+                background_color: Color::WHITE.into(),
+                border_color: Color::BLACK.into(),
+                ..default()
+            },
+            HudSection(HudPanel::Energy),
+        ))
+        .id();
+    let energy_bar_mask = commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    width: Val::Percent(100.),
+                    height: Val::Percent(100.),
+                    align_self: AlignSelf::Start,
+                    position_type: PositionType::Relative,
+                    ..default()
+                },
+                background_color: Color::BLUE.into(),
+                z_index: ZIndex::Local(10),
+                ..default()
+            },
+            Hud,
+        ))
+        .id();
+    let energy_bar_label = commands
+        .spawn((
+            TextBundle {
+                text: Text::from_sections([
+                    TextSection::new(
+                        "0",
+                        TextStyle {
+                            font_size: 32.0,
+                            color: Color::BLACK,
+                            ..default()
+                        },
+                    ),
+                    TextSection::new(
+                        "\n",
+                        TextStyle {
+                            font_size: 32.0,
+                            color: Color::BLACK,
+                            ..default()
+                        },
+                    ),
+                    TextSection::new(
+                        "",
+                        TextStyle {
+                            font_size: 32.0,
+                            color: Color::BLACK,
+                            ..default()
+                        },
+                    ),
+                ]),
+                ..default()
+            },
+            Hud,
+        ))
+        .id();
+    let energy_bar_label_layout = commands
+        .spawn(NodeBundle {
+            style: Style {
+                width: Val::Percent(100.),
+                height: Val::Percent(100.),
+                position_type: PositionType::Absolute,
+                align_content: AlignContent::Center,
+                justify_content: JustifyContent::Center,
+                ..default()
+            },
+            z_index: ZIndex::Local(20),
+            ..default()
+        })
+        .id();
+
+    commands
+        .entity(energy_bar_layout)
+        .push_children(&[energy_bar_label_layout, energy_bar_mask]);
+    commands
+        .entity(energy_bar_label_layout)
+        .add_child(energy_bar_label);
+
+    // tick, time and weather, robot position and fps, stacked above the timeline
+    // and left of the minimap when it is in the same corner
+    let column = commands
+        .spawn(NodeBundle {
+            style: Style {
+                position_type: PositionType::Absolute,
+                bottom: Val::Vh(10.),
+                right: Val::Px(10. + minimap_room(&args, MinimapCorner::BottomRight)),
+                flex_direction: FlexDirection::Column,
+                align_items: AlignItems::FlexEnd,
+                row_gap: Val::Px(6.),
+                ..default()
+            },
+            ..default()
+        })
+        .id();
+    for panel in [HudPanel::Status, HudPanel::Robot, HudPanel::Fps] {
+        let text = commands
+            .spawn((
+                TextBundle::from_section(
+                    "",
+                    TextStyle {
+                        font_size: 18.0,
+                        color: Color::WHITE,
+                        ..default()
+                    },
+                ),
+                HudText(panel),
+            ))
+            .id();
+        let node = commands
+            .spawn((
+                NodeBundle {
+                    style: Style {
+                        display: display(panel),
+                        padding: UiRect::all(Val::Px(6.)),
+                        ..default()
+                    },
+                    background_color: Color::rgba(0.0, 0.0, 0.0, 0.6).into(),
+                    ..default()
+                },
+                HudSection(panel),
+            ))
+            .add_child(text)
+            .id();
+        commands.entity(column).add_child(node);
+    }
+}
+
+// f1 to f4 hide or show the energy, status, robot and fps panels
+pub fn hud_keyboard(
+    keyboard_input: Res<Input<KeyCode>>,
+    mut query: Query<(&mut Style, &HudSection)>,
+) {
+    let Some(panel) = HudPanel::ALL
+        .into_iter()
+        .find(|panel| keyboard_input.just_pressed(panel.key()))
+    else {
+        return;
+    };
+    for (mut style, section) in query.iter_mut() {
+        if section.0 == panel {
+            style.display = match style.display {
+                Display::None => Display::Flex,
+                _ => Display::None,
+            };
+        }
+    }
+}
+
+pub fn update_hud(
+    current: Res<CurrentSnapshot>,
+    diagnostics: Res<DiagnosticsStore>,
+    mut query_hud_text: Query<&mut Text, With<Hud>>,
+    mut query_style: Query<&mut Style, (With<Hud>, Without<Text>)>,
+    mut query_text: Query<(&mut Text, &HudText), Without<Hud>>,
+) {
+    let fps = diagnostics
+        .get(FrameTimeDiagnosticsPlugin::FPS)
+        .and_then(|fps| fps.smoothed());
+    for (mut text, section) in query_text.iter_mut() {
+        if section.0 == HudPanel::Fps {
+            text.sections[0].value = match fps {
+                Some(fps) => format!("{:.0} fps", fps),
+                None => String::from("-- fps"),
+            };
+        }
+    }
+
+    // the rest only changes with the snapshot on screen
+    if !current.is_changed() {
+        return;
+    }
+    let Some(snapshot) = current.0.as_ref() else {
+        return;
+    };
+    // get robot energy
+    let data = snapshot.energy as f32;
+    for mut text in query_hud_text.iter_mut() {
+        text.sections[0].value = format!("Energy: {:?}", data as usize);
+        text.sections[1].value = "/".into();
+        text.sections[2].value = format!("{:?}", snapshot.max_energy);
+    }
+    for mut style in query_style.iter_mut() {
+        style.width = Val::Percent(100. * (data / snapshot.max_energy.max(1) as f32).min(1.0))
+    }
+
+    let (row, col) = snapshot.robot_position;
+    let tile = snapshot
+        .map
+        .get(row)
+        .and_then(|cells| cells.get(col))
+        .and_then(|cell| cell.as_ref());
+    for (mut text, section) in query_text.iter_mut() {
+        match section.0 {
+            HudPanel::Status => {
+                text.sections[0].value = format!(
+                    "tick {}  {}  {}",
+                    snapshot.tick,
+                    snapshot.environment.get_time_of_day_string(),
+                    weather_name(&snapshot.environment.get_weather_condition())
+                );
+            }
+            HudPanel::Robot => {
+                text.sections[0].value = match tile {
+                    Some(tile) => {
+                        format!("row {}, col {}  {}", row, col, tile_name(&tile.tile_type))
+                    }
+                    None => format!("row {}, col {}", row, col),
+                };
+            }
+            HudPanel::Energy | HudPanel::Fps => {}
+        }
+    }
+}
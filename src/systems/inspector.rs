@@ -0,0 +1,194 @@
+use crate::cli::{Args, MinimapCorner};
+use crate::coords::TILE_PIXEL_SIZE;
+use crate::entities::{BigBrother, InspectorPanel, InspectorText};
+use crate::history::SnapshotHistory;
+use crate::resources::{CameraControl, FogView, GroundTruth, InspectedTile, MapInfo};
+use crate::snapshot::CurrentSnapshot;
+use crate::systems::minimap::minimap_room;
+use crate::tiles::{content_fill, content_name, content_quantity, tile_name};
+use crate::world::Content;
+
+use bevy::input::Input;
+use bevy::prelude::{
+    default, BuildChildren, Camera, Color, Commands, DetectChanges, Gizmos, GlobalTransform,
+    MouseButton, NodeBundle, Query, Res, ResMut, Vec2, Visibility, With,
+};
+use bevy::text::{Text, TextStyle};
+use bevy::ui::node_bundles::TextBundle;
+use bevy::ui::{Interaction, PositionType, Style, UiRect, Val, ZIndex};
+use bevy::window::{PrimaryWindow, Window};
+
+// distance of the tooltip from the cursor
+const TOOLTIP_OFFSET: f32 = 16.0;
+
+pub fn setup_inspector(mut commands: Commands) {
+    let text = commands
+        .spawn((
+            TextBundle::from_section(
+                "",
+                TextStyle {
+                    font_size: 18.0,
+                    color: Color::WHITE,
+                    ..default()
+                },
+            ),
+            InspectorText,
+        ))
+        .id();
+    commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    position_type: PositionType::Absolute,
+                    padding: UiRect::all(Val::Px(6.)),
+                    ..default()
+                },
+                background_color: Color::rgba(0.0, 0.0, 0.0, 0.75).into(),
+                visibility: Visibility::Hidden,
+                // over every other panel
+                z_index: ZIndex::Global(10),
+                ..default()
+            },
+            InspectorPanel,
+        ))
+        .add_child(text);
+}
+
+// tile under the cursor, a left click pins it or lets it go
+pub fn pick_tile(
+    mouse_input: Res<Input<MouseButton>>,
+    map_info: Res<MapInfo>,
+    control: Res<CameraControl>,
+    mut inspected: ResMut<InspectedTile>,
+    windows: Query<&Window, With<PrimaryWindow>>,
+    query_camera: Query<(&Camera, &GlobalTransform), With<BigBrother>>,
+    query_ui: Query<&Interaction>,
+) {
+    let (Ok(window), Ok((camera, camera_transform))) =
+        (windows.get_single(), query_camera.get_single())
+    else {
+        return;
+    };
+    // the minimap and the panels are ui too
+    let over_ui = query_ui
+        .iter()
+        .any(|interaction| *interaction != Interaction::None);
+    let cell = window
+        .cursor_position()
+        .filter(|_| !over_ui)
+        .and_then(|cursor| camera.viewport_to_world_2d(camera_transform, cursor))
+        .and_then(|world| map_info.grid().cell_at(world));
+
+    if inspected.hovered != cell {
+        inspected.hovered = cell;
+    }
+    // releasing after dragging the camera around isn't a click
+    if mouse_input.just_released(MouseButton::Left) && !over_ui && !control.dragged {
+        // clicking the pinned tile again, or outside the map, lets it go
+        inspected.pinned = if inspected.pinned == cell { None } else { cell };
+    }
+}
+
+// tooltip next to the cursor, or a fixed panel for the pinned tile
+pub fn update_inspector(
+    args: Res<Args>,
+    inspected: Res<InspectedTile>,
+    current: Res<CurrentSnapshot>,
+    history: Res<SnapshotHistory>,
+    fog_view: Res<FogView>,
+    ground_truth: Res<GroundTruth>,
+    map_info: Res<MapInfo>,
+    windows: Query<&Window, With<PrimaryWindow>>,
+    mut query_panel: Query<(&mut Style, &mut Visibility), With<InspectorPanel>>,
+    mut query_text: Query<&mut Text, With<InspectorText>>,
+    mut gizmos: Gizmos,
+) {
+    let Ok((mut style, mut visibility)) = query_panel.get_single_mut() else {
+        return;
+    };
+    let (Some(snapshot), Some((row, col))) =
+        (current.0.as_ref(), inspected.pinned.or(inspected.hovered))
+    else {
+        *visibility = Visibility::Hidden;
+        return;
+    };
+    let Some(cell) = snapshot.map.get(row).and_then(|cells| cells.get(col)) else {
+        *visibility = Visibility::Hidden;
+        return;
+    };
+    *visibility = Visibility::Inherited;
+
+    // outline the tile being inspected
+    let color = if inspected.pinned.is_some() {
+        Color::YELLOW
+    } else {
+        Color::WHITE
+    };
+    gizmos.rect_2d(
+        map_info.grid().cell_center(row, col),
+        0.0,
+        Vec2::splat(TILE_PIXEL_SIZE),
+        color,
+    );
+
+    if inspected.pinned.is_some() {
+        // right of the minimap when it is in the same corner
+        style.left = Val::Px(10. + minimap_room(&args, MinimapCorner::BottomLeft));
+        style.top = Val::Auto;
+        style.bottom = Val::Px(60.);
+    } else if let Some(cursor) = windows.get_single().ok().and_then(|w| w.cursor_position()) {
+        style.left = Val::Px(cursor.x + TOOLTIP_OFFSET);
+        style.top = Val::Px(cursor.y + TOOLTIP_OFFSET);
+        style.bottom = Val::Auto;
+    }
+
+    if !(inspected.is_changed() || current.is_changed() || fog_view.is_changed()) {
+        return;
+    }
+    let mut lines = vec![format!("row {}, col {}", row, col)];
+    match cell.as_ref().map(|tile| (tile, "discovered")).or_else(|| {
+        ground_truth
+            .tile(*fog_view, row, col)
+            .map(|tile| (tile, "not discovered (initial world)"))
+    }) {
+        Some((tile, known)) => {
+            lines.push(format!(
+                "{}, elevation {}",
+                tile_name(&tile.tile_type),
+                tile.elevation
+            ));
+            lines.push(format!("content: {}", describe_content(&tile.content)));
+            lines.push(known.to_string());
+        }
+        None => lines.push(String::from("not discovered")),
+    }
+    lines.push(
+        match history
+            .index_of(snapshot.tick)
+            .and_then(|index| history.last_change(index, row, col))
+        {
+            Some(tick) => format!("last changed at tick {}", tick),
+            None => match history.iter().next() {
+                Some(oldest) => format!("unchanged since tick {}", oldest.tick),
+                None => String::from("unchanged"),
+            },
+        },
+    );
+    if inspected.pinned.is_some() {
+        lines.push(String::from("click to unpin"));
+    }
+    for mut text in query_text.iter_mut() {
+        text.sections[0].value = lines.join("\n");
+    }
+}
+
+// name with the quantity or the fill, "coin x5" or "bank 3/10"
+fn describe_content(content: &Content) -> String {
+    if let Some((stored, capacity)) = content_fill(content) {
+        format!("{} {}/{}", content_name(content), stored, capacity)
+    } else if let Some(quantity) = content_quantity(content) {
+        format!("{} x{}", content_name(content), quantity)
+    } else {
+        content_name(content).to_string()
+    }
+}
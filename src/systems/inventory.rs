@@ -0,0 +1,251 @@
+use crate::cli::{Args, MinimapCorner};
+use crate::components::InventoryRow;
+use crate::entities::{InventoryFill, InventoryRows, InventoryText};
+use crate::events::TickEvent;
+use crate::resources::{ContentIcons, InventoryHighlights};
+use crate::snapshot::BackpackSnapshot;
+use crate::systems::minimap::minimap_room;
+use crate::tiles::{content_name, content_texture_index, CONTENTS_ATLAS};
+
+use bevy::asset::{AssetServer, Assets};
+use bevy::prelude::{
+    default, BuildChildren, Color, Commands, DespawnRecursiveExt, Entity, EventReader, NodeBundle,
+    Query, Res, ResMut, Time, Vec2, With,
+};
+use bevy::sprite::TextureAtlas;
+use bevy::text::{Text, TextStyle};
+use bevy::ui::node_bundles::{AtlasImageBundle, TextBundle};
+use bevy::ui::{
+    AlignItems, BackgroundColor, FlexDirection, PositionType, Style, UiRect, UiTextureAtlasImage,
+    Val,
+};
+
+use std::collections::BTreeMap;
+
+// seconds a row stays highlighted after its count changes
+const HIGHLIGHT_SECONDS: f32 = 1.5;
+const ICON_SIZE: f32 = 24.0;
+
+pub fn setup_inventory(
+    mut commands: Commands,
+    args: Res<Args>,
+    asset_server: Res<AssetServer>,
+    mut atlases: ResMut<Assets<TextureAtlas>>,
+) {
+    let atlas = TextureAtlas::from_grid(
+        asset_server.load(CONTENTS_ATLAS),
+        Vec2::new(64.0, 64.0),
+        16,
+        1,
+        None,
+        None,
+    );
+    commands.insert_resource(ContentIcons(atlases.add(atlas)));
+
+    let panel = commands
+        .spawn(NodeBundle {
+            style: Style {
+                position_type: PositionType::Absolute,
+                // below the playback controls
+                top: Val::Px(80. + minimap_room(&args, MinimapCorner::TopRight)),
+                right: Val::Px(10.),
+                width: Val::Px(220.),
+                flex_direction: FlexDirection::Column,
+                padding: UiRect::all(Val::Px(6.)),
+                row_gap: Val::Px(4.),
+                ..default()
+            },
+            background_color: Color::rgba(0.0, 0.0, 0.0, 0.6).into(),
+            ..default()
+        })
+        .id();
+    let title = commands
+        .spawn((
+            TextBundle::from_section(
+                "backpack",
+                TextStyle {
+                    font_size: 20.0,
+                    color: Color::WHITE,
+                    ..default()
+                },
+            ),
+            InventoryText,
+        ))
+        .id();
+    let fill_bar = commands
+        .spawn(NodeBundle {
+            style: Style {
+                width: Val::Percent(100.),
+                height: Val::Px(8.),
+                ..default()
+            },
+            background_color: Color::rgba(0.2, 0.2, 0.2, 0.8).into(),
+            ..default()
+        })
+        .with_children(|parent| {
+            parent.spawn((
+                NodeBundle {
+                    style: Style {
+                        width: Val::Percent(0.),
+                        height: Val::Percent(100.),
+                        ..default()
+                    },
+                    background_color: Color::rgb(1.0, 0.85, 0.3).into(),
+                    ..default()
+                },
+                InventoryFill,
+            ));
+        })
+        .id();
+    let rows = commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    flex_direction: FlexDirection::Column,
+                    row_gap: Val::Px(2.),
+                    ..default()
+                },
+                ..default()
+            },
+            InventoryRows,
+        ))
+        .id();
+    commands
+        .entity(panel)
+        .push_children(&[title, fill_bar, rows]);
+}
+
+// rebuild the item list whenever the snapshot on screen changes
+pub fn update_inventory(
+    mut commands: Commands,
+    icons: Res<ContentIcons>,
+    mut highlights: ResMut<InventoryHighlights>,
+    mut events: EventReader<TickEvent>,
+    query_rows: Query<Entity, With<InventoryRows>>,
+    mut query_text: Query<&mut Text, With<InventoryText>>,
+    mut query_fill: Query<&mut Style, With<InventoryFill>>,
+) {
+    let Some(event) = events.iter().last() else {
+        return;
+    };
+    let backpack = &event.0.backpack;
+    let counts = count_by_content(backpack);
+    let used: usize = counts.values().map(|(_, amount)| amount).sum();
+
+    // highlight what changed since the last snapshot shown
+    let changed: Vec<(u32, bool)> = counts
+        .keys()
+        .chain(highlights.previous.keys())
+        .filter_map(|index| {
+            let before = highlights.previous.get(index).map(|(_, amount)| *amount);
+            let after = counts.get(index).map(|(_, amount)| *amount);
+            (before != after).then(|| (*index, after.unwrap_or(0) > before.unwrap_or(0)))
+        })
+        .collect();
+    for (index, added) in changed {
+        highlights.flashes.insert(index, (HIGHLIGHT_SECONDS, added));
+    }
+    // items that just left the backpack stay listed while they flash
+    let mut shown = counts.clone();
+    for (index, (name, _)) in highlights.previous.iter() {
+        if !shown.contains_key(index) && highlights.flashes.contains_key(index) {
+            shown.insert(*index, (*name, 0));
+        }
+    }
+    highlights.previous = counts;
+
+    for mut text in query_text.iter_mut() {
+        text.sections[0].value = format!("backpack {}/{}", used, backpack.size);
+    }
+    for mut style in query_fill.iter_mut() {
+        let fraction = if backpack.size > 0 {
+            (used as f32 / backpack.size as f32).min(1.0)
+        } else {
+            0.0
+        };
+        style.width = Val::Percent(100. * fraction);
+    }
+
+    for rows in query_rows.iter() {
+        commands.entity(rows).despawn_descendants();
+        for (index, (name, amount)) in shown.iter() {
+            let row = commands
+                .spawn((
+                    NodeBundle {
+                        style: Style {
+                            flex_direction: FlexDirection::Row,
+                            align_items: AlignItems::Center,
+                            column_gap: Val::Px(6.),
+                            padding: UiRect::horizontal(Val::Px(2.)),
+                            ..default()
+                        },
+                        ..default()
+                    },
+                    InventoryRow(*index),
+                ))
+                .with_children(|parent| {
+                    parent.spawn(AtlasImageBundle {
+                        style: Style {
+                            width: Val::Px(ICON_SIZE),
+                            height: Val::Px(ICON_SIZE),
+                            ..default()
+                        },
+                        texture_atlas: icons.0.clone(),
+                        texture_atlas_image: UiTextureAtlasImage {
+                            index: *index as usize,
+                            ..default()
+                        },
+                        ..default()
+                    });
+                    parent.spawn(TextBundle::from_section(
+                        format!("{} x{}", name, amount),
+                        TextStyle {
+                            font_size: 18.0,
+                            color: Color::WHITE,
+                            ..default()
+                        },
+                    ));
+                })
+                .id();
+            commands.entity(rows).add_child(row);
+        }
+    }
+}
+
+// fade the highlight of rows whose count changed
+pub fn fade_inventory_highlights(
+    time: Res<Time>,
+    mut highlights: ResMut<InventoryHighlights>,
+    mut query: Query<(&InventoryRow, &mut BackgroundColor)>,
+) {
+    if highlights.flashes.is_empty() {
+        return;
+    }
+    let delta = time.delta_seconds();
+    highlights.flashes.retain(|_, (remaining, _)| {
+        *remaining -= delta;
+        *remaining > 0.0
+    });
+    for (row, mut background) in query.iter_mut() {
+        background.0 = match highlights.flashes.get(&row.0) {
+            Some((remaining, true)) => Color::rgba(0.2, 0.8, 0.2, remaining / HIGHLIGHT_SECONDS),
+            Some((remaining, false)) => Color::rgba(0.8, 0.2, 0.2, remaining / HIGHLIGHT_SECONDS),
+            None => Color::NONE,
+        };
+    }
+}
+
+// backpack contents grouped by icon, the key inside Content is ignored
+fn count_by_content(backpack: &BackpackSnapshot) -> BTreeMap<u32, (&'static str, usize)> {
+    let mut counts = BTreeMap::new();
+    for (content, amount) in backpack.contents.iter() {
+        if *amount == 0 {
+            continue;
+        }
+        let entry = counts
+            .entry(content_texture_index(content))
+            .or_insert((content_name(content), 0));
+        entry.1 += amount;
+    }
+    counts
+}
@@ -0,0 +1,197 @@
+use crate::coords::TILE_PIXEL_SIZE;
+use crate::entities::{BigBrother, ContentLabel};
+use crate::events::MapDiffEvent;
+use crate::resources::{ContentLabels, FogView, GroundTruth, MapInfo};
+use crate::snapshot::CurrentSnapshot;
+use crate::systems::changed_cells;
+use crate::tiles::{content_fill, content_quantity};
+use crate::world::Content;
+
+use bevy::input::Input;
+use bevy::math::Vec3;
+use bevy::prelude::{
+    default, BuildChildren, Color, Commands, DespawnRecursiveExt, DetectChanges, Entity,
+    EventReader, KeyCode, Query, Res, ResMut, SpatialBundle, SpriteBundle, Transform, Vec2,
+    Visibility, With,
+};
+use bevy::render::camera::OrthographicProjection;
+use bevy::sprite::{Anchor, Sprite};
+use bevy::text::{Text, Text2dBundle, TextStyle};
+
+// labels are hidden when zoomed out further than this
+const MAX_LABEL_SCALE: f32 = 1.5;
+const LABEL_FONT_SIZE: f32 = 16.0;
+// rough width of a character at LABEL_FONT_SIZE, to size the badge behind it
+const CHARACTER_WIDTH: f32 = 9.0;
+// above the contents and the fog, under the robot
+const LABEL_Z: f32 = 8.0;
+
+// q: hide or show the labels, they also hide when zoomed out
+pub fn content_labels_keyboard(
+    keyboard_input: Res<Input<KeyCode>>,
+    query_camera: Query<&OrthographicProjection, With<BigBrother>>,
+    mut labels: ResMut<ContentLabels>,
+    mut query: Query<&mut Visibility, With<ContentLabel>>,
+) {
+    if keyboard_input.just_pressed(KeyCode::Q) {
+        labels.enabled = !labels.enabled;
+    }
+    let zoomed_in = query_camera
+        .get_single()
+        .map_or(true, |projection| projection.scale <= MAX_LABEL_SCALE);
+    let visible = labels.enabled && zoomed_in;
+    if visible == labels.visible {
+        return;
+    }
+    labels.visible = visible;
+    for mut visibility in query.iter_mut() {
+        *visibility = if visible {
+            Visibility::Inherited
+        } else {
+            Visibility::Hidden
+        };
+    }
+}
+
+// quantity badges on contents, and how full banks, bins and crates are
+pub fn update_content_labels(
+    mut commands: Commands,
+    mut events: EventReader<MapDiffEvent>,
+    current: Res<CurrentSnapshot>,
+    fog_view: Res<FogView>,
+    ground_truth: Res<GroundTruth>,
+    map_info: Res<MapInfo>,
+    mut labels: ResMut<ContentLabels>,
+) {
+    let Some(snapshot) = current.0.as_ref() else {
+        return;
+    };
+    let Some((cells, all)) = changed_cells(&mut events, fog_view.is_changed(), &snapshot.map)
+    else {
+        return;
+    };
+    if all {
+        for (_, entity) in labels.entities.drain() {
+            commands.entity(entity).despawn_recursive();
+        }
+    }
+
+    let grid = map_info.grid();
+    let visibility = if labels.visible {
+        Visibility::Inherited
+    } else {
+        Visibility::Hidden
+    };
+    for (row, col) in cells {
+        if let Some(entity) = labels.entities.remove(&(row, col)) {
+            commands.entity(entity).despawn_recursive();
+        }
+        let Some(content) = snapshot.map[row][col]
+            .as_ref()
+            .or_else(|| ground_truth.tile(*fog_view, row, col))
+            .map(|tile| &tile.content)
+        else {
+            continue;
+        };
+        let (text, fill) = if let Some((stored, capacity)) = content_fill(content) {
+            let fraction = if capacity > 0 {
+                stored as f32 / capacity as f32
+            } else {
+                1.0
+            };
+            (format!("{}/{}", stored, capacity), Some(fraction))
+        } else if let Some(quantity) = content_quantity(content) {
+            (quantity.to_string(), None)
+        } else {
+            continue;
+        };
+        // coins stand out, they are what most robots are after
+        let color = match content {
+            Content::Coin(_) => Color::GOLD,
+            _ => Color::WHITE,
+        };
+        let entity = spawn_label(
+            &mut commands,
+            grid.cell_center(row, col),
+            text,
+            color,
+            fill,
+            visibility,
+        );
+        labels.entities.insert((row, col), entity);
+    }
+}
+
+fn spawn_label(
+    commands: &mut Commands,
+    center: Vec2,
+    text: String,
+    color: Color,
+    fill: Option<f32>,
+    visibility: Visibility,
+) -> Entity {
+    let half = TILE_PIXEL_SIZE / 2.0;
+    // bottom right corner of the tile
+    let corner = Vec2::new(half - 2.0, -half + 2.0);
+    let badge_width = text.len() as f32 * CHARACTER_WIDTH + 6.0;
+    commands
+        .spawn((
+            ContentLabel,
+            SpatialBundle {
+                transform: Transform::from_translation(center.extend(LABEL_Z)),
+                visibility,
+                ..default()
+            },
+        ))
+        .with_children(|parent| {
+            parent.spawn(SpriteBundle {
+                sprite: Sprite {
+                    color: Color::rgba(0.0, 0.0, 0.0, 0.6),
+                    custom_size: Some(Vec2::new(badge_width, LABEL_FONT_SIZE + 2.0)),
+                    anchor: Anchor::BottomRight,
+                    ..default()
+                },
+                transform: Transform::from_translation(corner.extend(0.0)),
+                ..default()
+            });
+            parent.spawn(Text2dBundle {
+                text: Text::from_section(
+                    text,
+                    TextStyle {
+                        font_size: LABEL_FONT_SIZE,
+                        color,
+                        ..default()
+                    },
+                ),
+                text_anchor: Anchor::BottomRight,
+                transform: Transform::from_translation((corner + Vec2::new(-3.0, 1.0)).extend(0.1)),
+                ..default()
+            });
+            if let Some(fraction) = fill {
+                // bar along the top of the tile, turning red as it fills up
+                let fraction = fraction.clamp(0.0, 1.0);
+                let width = TILE_PIXEL_SIZE - 8.0;
+                let top = half - 6.0;
+                parent.spawn(SpriteBundle {
+                    sprite: Sprite {
+                        color: Color::rgba(0.0, 0.0, 0.0, 0.6),
+                        custom_size: Some(Vec2::new(width + 2.0, 6.0)),
+                        ..default()
+                    },
+                    transform: Transform::from_translation(Vec3::new(0.0, top, 0.0)),
+                    ..default()
+                });
+                parent.spawn(SpriteBundle {
+                    sprite: Sprite {
+                        color: Color::rgb(fraction, 1.0 - fraction, 0.2),
+                        custom_size: Some(Vec2::new(width * fraction, 4.0)),
+                        anchor: Anchor::CenterLeft,
+                        ..default()
+                    },
+                    transform: Transform::from_translation(Vec3::new(-width / 2.0, top, 0.1)),
+                    ..default()
+                });
+            }
+        })
+        .id()
+}
@@ -0,0 +1,278 @@
+use crate::cli::{Args, MinimapCorner};
+use crate::coords::TILE_PIXEL_SIZE;
+use crate::entities::{BigBrother, MinimapPanel, MinimapRobot, MinimapView, VisualizerRobot};
+use crate::events::MapDiffEvent;
+use crate::resources::{CameraControl, FogView, GroundTruth, MapInfo, Minimap};
+use crate::snapshot::CurrentSnapshot;
+use crate::systems::changed_cells;
+use crate::tiles::tile_color;
+
+use bevy::asset::Assets;
+use bevy::input::Input;
+use bevy::prelude::{
+    default, BuildChildren, ButtonBundle, Color, Commands, DetectChanges, EventReader,
+    GlobalTransform, Image, KeyCode, NodeBundle, Query, Res, ResMut, Transform, Vec2, Visibility,
+    With, Without,
+};
+use bevy::render::camera::OrthographicProjection;
+use bevy::render::render_resource::{Extent3d, TextureDimension, TextureFormat};
+use bevy::ui::node_bundles::ImageBundle;
+use bevy::ui::{Interaction, Node, Overflow, PositionType, Style, UiImage, UiRect, Val};
+use bevy::window::{PrimaryWindow, Window};
+
+// undiscovered tiles, same as the fog
+const FOG_PIXEL: [u8; 4] = [30, 30, 38, 255];
+const ROBOT_MARKER_PIXELS: f32 = 6.0;
+const MARGIN: f32 = 10.0;
+
+// how far from the window edge panels in `corner` start so they don't cover the
+// minimap, the longer side of the map is reserved whichever way it lies
+pub fn minimap_room(args: &Args, corner: MinimapCorner) -> f32 {
+    if args.minimap_corner == corner {
+        args.minimap_size as f32 + MARGIN
+    } else {
+        0.0
+    }
+}
+
+// one pixel per tile, drawn on the ui instead of a second camera over the tilemaps
+pub fn setup_minimap(
+    mut commands: Commands,
+    args: Res<Args>,
+    map_info: Res<MapInfo>,
+    mut images: ResMut<Assets<Image>>,
+) {
+    let grid = map_info.grid();
+    let image = images.add(Image::new_fill(
+        Extent3d {
+            width: grid.cols.max(1),
+            height: grid.rows.max(1),
+            depth_or_array_layers: 1,
+        },
+        TextureDimension::D2,
+        &FOG_PIXEL,
+        TextureFormat::Rgba8UnormSrgb,
+    ));
+
+    // the longer side of the map gets the configured size
+    let longest = grid.rows.max(grid.cols).max(1) as f32;
+    let size = args.minimap_size as f32;
+    let width = size * grid.cols.max(1) as f32 / longest;
+    let height = size * grid.rows.max(1) as f32 / longest;
+    let (top, bottom, left, right) = match args.minimap_corner {
+        MinimapCorner::TopLeft => (Val::Px(MARGIN), Val::Auto, Val::Px(MARGIN), Val::Auto),
+        MinimapCorner::TopRight => (Val::Px(MARGIN), Val::Auto, Val::Auto, Val::Px(MARGIN)),
+        // above the timeline
+        MinimapCorner::BottomLeft => (Val::Auto, Val::Vh(10.), Val::Px(MARGIN), Val::Auto),
+        MinimapCorner::BottomRight => (Val::Auto, Val::Vh(10.), Val::Auto, Val::Px(MARGIN)),
+    };
+
+    let map = commands
+        .spawn(ImageBundle {
+            style: Style {
+                width: Val::Percent(100.),
+                height: Val::Percent(100.),
+                ..default()
+            },
+            image: UiImage::new(image.clone()),
+            ..default()
+        })
+        .id();
+    let view = commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    position_type: PositionType::Absolute,
+                    border: UiRect::all(Val::Px(1.)),
+                    ..default()
+                },
+                border_color: Color::WHITE.into(),
+                ..default()
+            },
+            MinimapView,
+        ))
+        .id();
+    let robot = commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    position_type: PositionType::Absolute,
+                    width: Val::Px(ROBOT_MARKER_PIXELS),
+                    height: Val::Px(ROBOT_MARKER_PIXELS),
+                    margin: UiRect {
+                        left: Val::Px(-ROBOT_MARKER_PIXELS / 2.),
+                        top: Val::Px(-ROBOT_MARKER_PIXELS / 2.),
+                        ..default()
+                    },
+                    ..default()
+                },
+                background_color: Color::RED.into(),
+                ..default()
+            },
+            MinimapRobot,
+        ))
+        .id();
+    commands
+        .spawn((
+            ButtonBundle {
+                style: Style {
+                    position_type: PositionType::Absolute,
+                    top,
+                    bottom,
+                    left,
+                    right,
+                    width: Val::Px(width),
+                    height: Val::Px(height),
+                    border: UiRect::all(Val::Px(2.)),
+                    overflow: Overflow::clip(),
+                    ..default()
+                },
+                border_color: Color::rgba(0.0, 0.0, 0.0, 0.8).into(),
+                background_color: Color::BLACK.into(),
+                ..default()
+            },
+            MinimapPanel,
+        ))
+        .push_children(&[map, view, robot]);
+
+    commands.insert_resource(Minimap {
+        image,
+        visible: true,
+    });
+}
+
+// m: hide or show the minimap
+pub fn minimap_keyboard(
+    keyboard_input: Res<Input<KeyCode>>,
+    mut minimap: ResMut<Minimap>,
+    mut query: Query<&mut Visibility, With<MinimapPanel>>,
+) {
+    if !keyboard_input.just_pressed(KeyCode::M) {
+        return;
+    }
+    minimap.visible = !minimap.visible;
+    for mut visibility in query.iter_mut() {
+        *visibility = if minimap.visible {
+            Visibility::Inherited
+        } else {
+            Visibility::Hidden
+        };
+    }
+}
+
+// recolor the pixels of the tiles that changed
+pub fn update_minimap_image(
+    mut events: EventReader<MapDiffEvent>,
+    current: Res<CurrentSnapshot>,
+    fog_view: Res<FogView>,
+    ground_truth: Res<GroundTruth>,
+    map_info: Res<MapInfo>,
+    minimap: Option<Res<Minimap>>,
+    mut images: ResMut<Assets<Image>>,
+) {
+    let (Some(snapshot), Some(minimap)) = (current.0.as_ref(), minimap) else {
+        return;
+    };
+    let Some((cells, _)) = changed_cells(&mut events, fog_view.is_changed(), &snapshot.map) else {
+        return;
+    };
+    let Some(image) = images.get_mut(&minimap.image) else {
+        return;
+    };
+    let grid = map_info.grid();
+    let width = image.texture_descriptor.size.width as usize;
+    for (row, col) in cells {
+        if !grid.contains(row, col) || col >= width {
+            continue;
+        }
+        let pixel = match (
+            snapshot.map[row][col].as_ref(),
+            ground_truth.tile(*fog_view, row, col),
+        ) {
+            (Some(tile), _) => tile_color(&tile.tile_type),
+            // the real world, darkened where the robot hasn't been
+            (None, Some(tile)) => {
+                let [r, g, b, a] = tile_color(&tile.tile_type);
+                [r / 2, g / 2, b / 2, a]
+            }
+            (None, None) => FOG_PIXEL,
+        };
+        let start = (row * width + col) * 4;
+        if let Some(data) = image.data.get_mut(start..start + 4) {
+            data.copy_from_slice(&pixel);
+        }
+    }
+}
+
+// the main camera's view and the robot, in percent of the minimap
+pub fn update_minimap_markers(
+    map_info: Res<MapInfo>,
+    windows: Query<&Window, With<PrimaryWindow>>,
+    query_camera: Query<(&Transform, &OrthographicProjection), With<BigBrother>>,
+    query_robot: Query<&Transform, (With<VisualizerRobot>, Without<BigBrother>)>,
+    mut query_view: Query<&mut Style, (With<MinimapView>, Without<MinimapRobot>)>,
+    mut query_marker: Query<&mut Style, (With<MinimapRobot>, Without<MinimapView>)>,
+) {
+    let (Ok(window), Ok((camera, projection))) = (windows.get_single(), query_camera.get_single())
+    else {
+        return;
+    };
+    let grid = map_info.grid();
+    let map_size = grid.pixel_size().max(Vec2::ONE);
+    // tile centers are on the grid, so the map starts half a tile before zero
+    let to_percent = |world: Vec2| {
+        let fraction = (world + Vec2::splat(TILE_PIXEL_SIZE / 2.0)) / map_size;
+        Vec2::new(fraction.x, 1.0 - fraction.y) * 100.0
+    };
+
+    let half_view = Vec2::new(window.width(), window.height()) * projection.scale / 2.0;
+    let center = camera.translation.truncate();
+    let top_left = to_percent(center + Vec2::new(-half_view.x, half_view.y));
+    let bottom_right = to_percent(center + Vec2::new(half_view.x, -half_view.y));
+    for mut style in query_view.iter_mut() {
+        style.left = Val::Percent(top_left.x);
+        style.top = Val::Percent(top_left.y);
+        style.width = Val::Percent(bottom_right.x - top_left.x);
+        style.height = Val::Percent(bottom_right.y - top_left.y);
+    }
+
+    if let Ok(robot) = query_robot.get_single() {
+        let position = to_percent(robot.translation.truncate());
+        for mut style in query_marker.iter_mut() {
+            style.left = Val::Percent(position.x);
+            style.top = Val::Percent(position.y);
+        }
+    }
+}
+
+// click or drag on the minimap to move the main camera there
+pub fn minimap_click(
+    map_info: Res<MapInfo>,
+    mut control: ResMut<CameraControl>,
+    windows: Query<&Window, With<PrimaryWindow>>,
+    query: Query<(&Interaction, &Node, &GlobalTransform), With<MinimapPanel>>,
+    mut query_camera: Query<&mut Transform, With<BigBrother>>,
+) {
+    let Some(cursor) = windows
+        .get_single()
+        .ok()
+        .and_then(|window| window.cursor_position())
+    else {
+        return;
+    };
+    for (interaction, node, transform) in query.iter() {
+        if *interaction != Interaction::Pressed {
+            continue;
+        }
+        let size = node.size().max(Vec2::ONE);
+        let top_left = transform.translation().truncate() - size / 2.0;
+        let fraction = ((cursor - top_left) / size).clamp(Vec2::ZERO, Vec2::ONE);
+        let world = Vec2::new(fraction.x, 1.0 - fraction.y) * map_info.grid().pixel_size()
+            - Vec2::splat(TILE_PIXEL_SIZE / 2.0);
+        control.follow = false;
+        for mut camera in query_camera.iter_mut() {
+            camera.translation.x = world.x;
+            camera.translation.y = world.y;
+        }
+    }
+}
@@ -1,142 +1,100 @@
-use std::path::PathBuf;
-use crate::components::LastUpdate;
+pub mod camera;
+pub mod charts;
+pub mod event_log;
+pub mod fog;
+pub mod hud;
+pub mod inspector;
+pub mod inventory;
+pub mod labels;
+pub mod minimap;
+pub mod overlay;
+pub mod playback;
+pub mod timeline;
+pub mod trail;
+pub mod weather;
+
+use crate::charts::ChartData;
+use crate::components::{Facing, MotionKind, RobotMotion};
+use crate::coords::{MapGrid, TILE_PIXEL_SIZE};
 use crate::entities::VisualizerRobot;
-use crate::entities::HUD;
-use crate::entities::{BigBrother, ContentMap, MiniCamera};
-use crate::entities::{DncRectangle, TileMap};
-use crate::events::{CameraEvent, TickEvent};
-use crate::resources::GameTimer;
-use crate::resources::MapInfo;
-use crate::resources::RunnerTag;
-use crate::VISUALIZER_ENERGY;
-use crate::{
-    TILE_PIXEL_OFFSET, TILE_PIXEL_SIZE, VISUALIZER_MAP, VISUALIZER_ROBOT_POSITION, VISUALIZER_TIME,
+use crate::entities::{BigBrother, ContentLayer, ContentMap};
+use crate::entities::{
+    DncRectangle, FogLayer, FogMap, OverlayLayer, OverlayMap, TileLayer, TileMap,
 };
+use crate::events::{CameraEvent, MapDiffEvent, TickEvent};
+use crate::history::SnapshotHistory;
+use crate::lighting::{minutes_between, parse_time_of_day, LightingGradient};
+use crate::replay::ReplayRecorder;
+use crate::resources::CameraControl;
+use crate::resources::MapInfo;
+use crate::resources::PlaybackState;
+use crate::resources::Timeline;
+use crate::resources::{FogView, GroundTruth};
+use crate::snapshot::{CurrentSnapshot, SnapshotReceiver};
 
 use bevy::asset::{AssetServer, Handle};
-use bevy::core_pipeline::clear_color::ClearColorConfig;
 
 use bevy::ecs::query::Without;
-use bevy::input::Input;
-use bevy::math::UVec2;
 use bevy::math::Vec3;
 use bevy::prelude::{
-    default, Assets, BuildChildren, Camera, Camera2dBundle, Color, ColorMaterial, Commands,
-    EventReader, EventWriter, Image, KeyCode, NodeBundle, Query, Res, ResMut, SpriteBundle, Time,
-    Transform, Vec2, With,
+    default, Assets, Camera2dBundle, Color, ColorMaterial, Commands, DetectChanges, EventReader,
+    EventWriter, Image, Local, Query, Res, ResMut, SpriteBundle, Time, Transform, Vec2, With,
 };
-use bevy::render::camera::OrthographicProjection;
-use bevy::render::camera::Viewport;
-
-use bevy::sprite::Sprite;
-use bevy::text::Text;
-use bevy::text::TextSection;
-use bevy::text::TextStyle;
-use bevy::ui::node_bundles::TextBundle;
-use bevy::ui::AlignContent;
-use bevy::ui::AlignSelf;
-use bevy::ui::FlexDirection;
-use bevy::ui::JustifyContent;
-use bevy::ui::PositionType;
-use bevy::ui::Style;
-use bevy::ui::UiRect;
-use bevy::ui::Val;
-use bevy::ui::ZIndex;
-
-use bevy_ecs_tilemap::map::{TilemapId, TilemapSize, TilemapTexture, TilemapTileSize, TilemapType};
-use bevy_ecs_tilemap::prelude::{TileBundle, TilePos, TileStorage, TileTextureIndex};
-use bevy_ecs_tilemap::TilemapBundle;
-
-use oxagaudiotool::sound_config::OxAgSoundConfig;
-
-use robotics_lib::runner::Runner;
-use robotics_lib::world::tile::Content;
-use robotics_lib::world::tile::TileType;
-
-use saver_bot::SaverBot;
-use my_robot::MyRobot;
-
-use std::env;
-
-use worldgen_unwrap::public::WorldgeneratorUnwrap;
-// 🌯 runner wrapper 🌯
-use crate::wrapper::VisualizerRobotWrapper;
-
-pub fn game_prestartup(mut commands: Commands, mut game_timer: ResMut<GameTimer>) {
-    // Env args
-    let args: Vec<String> = env::args().collect();
-    println!("{} {} {}", &args[1], &args[2], &args[3]);
-    // Pause timer
-    game_timer.0.pause();
-
-    // Create bot, world, play audio
-    let coin_amount = &args[1];
-    let bot_choice = &args[3];
-
-    // Load background music
-    let background_music = OxAgSoundConfig::new_looped_with_volume("assets/default/music.ogg", 2.0);
 
-    // Create robot and world
-    let world_path = &args[2];
-    match bot_choice.as_str() {
-        "0" => {
-            let mut saver_bot = SaverBot::new(Some(coin_amount.parse::<usize>().unwrap()));
-            // Play background music
-            let _ = saver_bot.audio.play_audio(&background_music);
-            let robot = VisualizerRobotWrapper::new(saver_bot);
+use bevy::sprite::{Sprite, SpriteSheetBundle, TextureAtlas, TextureAtlasSprite};
 
-            let mut worldgen = WorldgeneratorUnwrap::init(false, Some(PathBuf::from(world_path.clone())));
-
-            // Process first tick, add runner to resource
-            let runner = Runner::new(Box::new(robot), &mut worldgen);
-            let _ = match runner {
-                Ok(mut runner) => {
-                    let _ = runner.game_tick();
-
-                    commands.insert_resource(RunnerTag(runner));
-                }
-                Err(err) => panic!("Error: {:?}", err),
-            };
-        },
-        "1" => {
-            let anastasia_bot = MyRobot::new();
-            let robot = VisualizerRobotWrapper::new(anastasia_bot);
-
-            let mut worldgen = WorldgeneratorUnwrap::init(false, Some(PathBuf::from(world_path.clone())));
-
-            // Process first tick, add runner to resource
-            let runner = Runner::new(Box::new(robot), &mut worldgen);
-            let _ = match runner {
-                Ok(mut runner) => {
-                    let _ = runner.game_tick();
-
-                    commands.insert_resource(RunnerTag(runner));
-                }
-                Err(err) => panic!("Error: {:?}", err),
-            };
-        }
-        _ => {}
-    }
+use bevy_ecs_tilemap::map::{TilemapId, TilemapTexture, TilemapTileSize, TilemapType};
+use bevy_ecs_tilemap::prelude::{TileBundle, TileStorage, TileTextureIndex, TileVisible};
+use bevy_ecs_tilemap::TilemapBundle;
 
-    game_timer.0.unpause();
-}
+use crate::tiles::{
+    content_texture_index, tile_texture_index, CONTENTS_ATLAS, FOG_ATLAS, FOG_INDEX, OVERLAY_ATLAS,
+    ROBOT_WALK_FRAMES, ROBOT_WALK_SHEET, TILES_ATLAS,
+};
 
-pub fn run_tick(
-    time: Res<Time>,
-    mut runner: ResMut<RunnerTag>,
-    mut timer: ResMut<GameTimer>,
+use crate::world::{Event, Tile, TileType};
+
+use std::time::Duration;
+
+// moves shorter than this are not animated
+const MIN_TWEEN_SECONDS: f32 = 0.05;
+const TELEPORT_SECONDS: f32 = 0.6;
+// clock changes bigger than this are shown at once instead of eased
+const MAX_EASED_MINUTES: f32 = 180.0;
+// how quickly the lighting catches up with the clock, per second
+const LIGHTING_EASING: f32 = 3.0;
+
+// drain the wrapper's channel, every system renders the latest snapshot
+// unless the timeline is looking at an older one
+pub fn receive_snapshots(
+    receiver: Res<SnapshotReceiver>,
+    mut current: ResMut<CurrentSnapshot>,
+    mut history: ResMut<SnapshotHistory>,
+    timeline: Res<Timeline>,
+    mut recorder: Option<ResMut<ReplayRecorder>>,
+    mut charts: ResMut<ChartData>,
     mut event: EventWriter<TickEvent>,
 ) {
-    if timer.0.tick(time.delta()).just_finished() {
-        println!("RUNNING");
-        println!("RUNNING");
-        println!("RUNNING");
-        println!("RUNNING");
-        println!("RUNNING");
-        println!("=========");
-        let _ = runner.0.game_tick();
-        // send tick update event
-        event.send(TickEvent);
+    for snapshot in receiver.0.try_iter() {
+        if let Some(recorder) = recorder.as_mut() {
+            if let Err(err) = recorder.0.write(&snapshot) {
+                eprintln!("{}", err);
+            }
+        }
+        history.push(&snapshot);
+        // charts keep every tick, also while looking back on the timeline
+        charts.record(&snapshot);
+        if timeline.viewing.is_none() {
+            current.0 = Some(snapshot.clone());
+            // send tick update event
+            event.send(TickEvent(snapshot));
+        }
+    }
+    // keep the file usable even if the app is killed
+    if let Some(recorder) = recorder.as_mut() {
+        if let Err(err) = recorder.0.flush() {
+            eprintln!("{}", err);
+        }
     }
 }
 
@@ -144,38 +102,35 @@ pub fn startup(
     mut commands: Commands,
     asset_server: Res<AssetServer>,
     mut map_info: ResMut<MapInfo>,
+    current: Res<CurrentSnapshot>,
+    mut atlases: ResMut<Assets<TextureAtlas>>,
     _materials: ResMut<Assets<ColorMaterial>>,
 ) {
     // get visualizer map
-    let data = VISUALIZER_MAP.lock().unwrap();
-    if let Some(rows) = &*data {
-        println!("num_rows {}", rows.len());
-        println!("num_rows {}", rows.len());
-        println!("num_rows {}", rows.len());
-        println!("num_rows {}", rows.len());
-        println!("num_rows {}", rows.len());
-        // save size in resource
-        map_info.size = rows.len() as u32;
+    if let Some(snapshot) = &current.0 {
+        let grid = MapGrid::of(&snapshot.map);
+        bevy::log::debug!("map size {}x{}", grid.rows, grid.cols);
+        // save size in resource, rows can be narrower than the widest one
+        map_info.rows = grid.rows;
+        map_info.cols = grid.cols;
     }
 
-    // spawn black rectangle for day/night cycle
+    // spawn rectangle over the map for day/night lighting
+    let grid = map_info.grid();
     commands.spawn((
         DncRectangle,
         SpriteBundle {
             sprite: Sprite {
-                color: Color::rgba(0., 0., 0., 0.),
-                custom_size: Some(Vec2::new(10000000000.0, 10000000000.0)),
+                color: Color::NONE,
+                custom_size: Some(grid.pixel_size()),
                 ..default()
             },
-            transform: Transform::from_translation(Vec3::new(0., 0., 50.)),
+            transform: Transform::from_translation(grid.center().extend(50.)),
             ..default()
         },
     ));
 
-    let map_size = TilemapSize {
-        x: map_info.size,
-        y: map_info.size,
-    };
+    let map_size = grid.tilemap_size();
     let tile_size = TilemapTileSize {
         x: TILE_PIXEL_SIZE,
         y: TILE_PIXEL_SIZE,
@@ -184,12 +139,11 @@ pub fn startup(
     let map_type = TilemapType::default();
     // tiles
     let mut tile_storage = TileStorage::empty(map_size);
-    let tilemap_entity = commands.spawn_empty().id();
-    let texture_handle: Handle<Image> = asset_server.load("tiles_robotic_lib.png");
+    let tilemap_entity = commands.spawn(TileLayer).id();
+    let texture_handle: Handle<Image> = asset_server.load(TILES_ATLAS);
 
-    for y in (0..map_info.size).rev() {
-        for x in 0..map_info.size {
-            let tile_pos = TilePos { x, y };
+    for (row, col) in grid.cells() {
+        if let Some(tile_pos) = grid.tile_pos(row, col) {
             let tile_entity = commands
                 .spawn((
                     TileBundle {
@@ -197,7 +151,6 @@ pub fn startup(
                         tilemap_id: TilemapId(tilemap_entity),
                         ..Default::default()
                     },
-                    LastUpdate::default(),
                     TileMap,
                 ))
                 .id();
@@ -218,14 +171,47 @@ pub fn startup(
         ..Default::default()
     });
 
+    // terrain overlay, hidden until one is picked
+    let overlay_texture: Handle<Image> = asset_server.load(OVERLAY_ATLAS);
+    let mut overlay_storage = TileStorage::empty(map_size);
+    let overlay_entity = commands.spawn(OverlayLayer).id();
+
+    for (row, col) in grid.cells() {
+        if let Some(tile_pos) = grid.tile_pos(row, col) {
+            let tile_entity = commands
+                .spawn((
+                    TileBundle {
+                        position: tile_pos,
+                        tilemap_id: TilemapId(overlay_entity),
+                        visible: TileVisible(false),
+                        ..Default::default()
+                    },
+                    OverlayMap,
+                ))
+                .id();
+            overlay_storage.set(&tile_pos, tile_entity);
+        }
+    }
+
+    // spawn overlay, between the tiles and the contents
+    commands.entity(overlay_entity).insert(TilemapBundle {
+        grid_size,
+        map_type,
+        size: map_size,
+        storage: overlay_storage,
+        texture: TilemapTexture::Single(overlay_texture),
+        tile_size,
+        transform: Transform::from_translation(Vec3::new(0.0, 0.0, 3.0)),
+        ..Default::default()
+    });
+
     // contents
-    let texture_handle2: Handle<Image> = asset_server.load("contents_robotic_lib.png");
+    let texture_handle2: Handle<Image> = asset_server.load(CONTENTS_ATLAS);
     let mut tile_storage2 = TileStorage::empty(map_size);
-    let tilemap_entity2 = commands.spawn_empty().id();
+    let tilemap_entity2 = commands.spawn(ContentLayer).id();
 
-    for y in (0..map_info.size).rev() {
-        for x in 0..map_info.size {
-            let tile_pos = TilePos { x, y };
+    for (row, col) in grid.cells() {
+        if let Some(tile_pos) = grid.tile_pos(row, col) {
             let tile_entity = commands
                 .spawn((
                     TileBundle {
@@ -233,7 +219,6 @@ pub fn startup(
                         tilemap_id: TilemapId(tilemap_entity2),
                         ..Default::default()
                     },
-                    LastUpdate::default(),
                     ContentMap,
                 ))
                 .id();
@@ -254,396 +239,368 @@ pub fn startup(
         ..Default::default()
     });
 
+    // fog of war, covers every tile until the robot discovers it
+    let texture_handle3: Handle<Image> = asset_server.load(FOG_ATLAS);
+    let mut tile_storage3 = TileStorage::empty(map_size);
+    let tilemap_entity3 = commands.spawn(FogLayer).id();
+
+    for (row, col) in grid.cells() {
+        if let Some(tile_pos) = grid.tile_pos(row, col) {
+            let tile_entity = commands
+                .spawn((
+                    TileBundle {
+                        position: tile_pos,
+                        tilemap_id: TilemapId(tilemap_entity3),
+                        texture_index: TileTextureIndex(FOG_INDEX),
+                        ..Default::default()
+                    },
+                    FogMap,
+                ))
+                .id();
+            tile_storage3.set(&tile_pos, tile_entity);
+        }
+    }
+
+    // spawn fog
+    commands.entity(tilemap_entity3).insert(TilemapBundle {
+        grid_size,
+        map_type,
+        size: map_size,
+        storage: tile_storage3,
+        texture: TilemapTexture::Single(texture_handle3),
+        tile_size,
+        transform: Transform::from_translation(Vec3::new(0.0, 0.0, 7.0)),
+        ..Default::default()
+    });
+
     // spawn robot
+    let robot_atlas = TextureAtlas::from_grid(
+        asset_server.load(ROBOT_WALK_SHEET),
+        Vec2::new(TILE_PIXEL_SIZE, TILE_PIXEL_SIZE),
+        ROBOT_WALK_FRAMES,
+        4,
+        None,
+        None,
+    );
     commands.spawn((
         VisualizerRobot,
-        SpriteBundle {
-            texture: asset_server.load("robot_64x64.png"),
+        SpriteSheetBundle {
+            texture_atlas: atlases.add(robot_atlas),
             //transform: Transform::from_translation(Vec3::new(-288.0,295.0,10.0)),
-            transform: Transform::from_translation(Vec3::new(0.0, 74.0, 10.0)),
+            transform: Transform::from_translation(
+                grid.robot_translation(
+                    map_info.last_known_robot_position.0,
+                    map_info.last_known_robot_position.1,
+                )
+                .extend(10.0),
+            ),
             ..default()
         },
+        RobotMotion::default(),
     ));
 
     // spawn camera
-    commands.spawn((Camera2dBundle::default(), BigBrother));
+    let mut camera = Camera2dBundle::default();
+    camera.projection.scale = 0.6;
+    commands.spawn((camera, BigBrother));
 }
 
-pub fn setup_hud(mut commands: Commands) {
-    let energy_bar_layout = commands
-        .spawn(NodeBundle {
-            style: Style {
-                width: Val::Vw(95.0),
-                height: Val::Vh(5.),
-                border: UiRect::all(Val::Px(2.)),
-                flex_direction: FlexDirection::Column,
-                align_self: AlignSelf::FlexEnd,
-                ..default()
-            },
+// update day night cycle, eased towards the time of the snapshot on screen
+pub fn update_dnc(
+    time: Res<Time>,
+    current: Res<CurrentSnapshot>,
+    gradient: Res<LightingGradient>,
+    mut map_info: ResMut<MapInfo>,
+    mut shown_minute: Local<Option<f32>>,
+    mut query: Query<&mut Sprite, With<DncRectangle>>,
+) {
+    // get world time, odd strings keep the last known time
+    let Some(target) = current
+        .0
+        .as_ref()
+        .and_then(|snapshot| parse_time_of_day(&snapshot.environment.get_time_of_day_string()))
+    else {
+        return;
+    };
+    map_info.current_time = ((target / 60.) as u8, (target % 60.) as u8);
 
-            background_color: Color::WHITE.into(),
-            border_color: Color::BLACK.into(),
-            ..default()
-        })
-        .id();
-    let energy_bar_mask = commands
-        .spawn((
-            NodeBundle {
-                style: Style {
-                    width: Val::Percent(100.),
-                    height: Val::Percent(100.),
-                    align_self: AlignSelf::Start,
-                    position_type: PositionType::Relative,
-                    ..default()
-                },
-                background_color: Color::BLUE.into(),
-                z_index: ZIndex::Local(10),
-                ..default()
-            },
-            HUD,
-        ))
-        .id();
-    let energy_bar_label = commands
-        .spawn((
-            TextBundle {
-                text: Text::from_sections([
-                    TextSection::new(
-                        "0",
-                        TextStyle {
-                            font_size: 32.0,
-                            color: Color::BLACK.into(),
-                            ..default()
-                        },
-                    ),
-                    TextSection::new(
-                        "\n",
-                        TextStyle {
-                            font_size: 32.0,
-                            color: Color::BLACK.into(),
-                            ..default()
-                        },
-                    ),
-                    TextSection::new(
-                        "",
-                        TextStyle {
-                            font_size: 32.0,
-                            color: Color::BLACK.into(),
-                            ..default()
-                        },
-                    ),
-                ]),
-                ..default()
-            },
-            HUD,
-        ))
-        .id();
-    let energy_bar_label_layout = commands
-        .spawn(NodeBundle {
-            style: Style {
-                width: Val::Percent(100.),
-                height: Val::Percent(100.),
-                position_type: PositionType::Absolute,
-                align_content: AlignContent::Center,
-                justify_content: JustifyContent::Center,
-                ..default()
-            },
-            z_index: ZIndex::Local(20),
-            ..default()
-        })
-        .id();
-
-    commands
-        .entity(energy_bar_layout)
-        .push_children(&[energy_bar_label_layout, energy_bar_mask]);
-    commands
-        .entity(energy_bar_label_layout)
-        .add_child(energy_bar_label);
+    // ticks move the clock in steps, glide between them instead of jumping
+    let minute = match *shown_minute {
+        Some(shown) if minutes_between(shown, target).abs() <= MAX_EASED_MINUTES => {
+            let eased = 1.0 - (-LIGHTING_EASING * time.delta_seconds()).exp();
+            shown + minutes_between(shown, target) * eased
+        }
+        // first frame or a jump along the timeline
+        _ => target,
+    };
+    *shown_minute = Some(minute);
+
+    for mut sprite in query.iter_mut() {
+        sprite.color = gradient.sample(minute);
+    }
 }
 
-pub fn update_hud(
-    mut query_hud_text: Query<&mut Text, With<HUD>>,
-    mut query_style: Query<&mut Style, (With<HUD>, Without<Text>)>,
+// func to change sprite position based on actual robot position
+pub fn update_robot_position(
+    mut query: Query<(&Transform, &mut RobotMotion), With<VisualizerRobot>>,
+    mut map_info: ResMut<MapInfo>,
+    playback: Res<PlaybackState>,
     mut events: EventReader<TickEvent>,
+    mut event_w: EventWriter<CameraEvent>,
 ) {
-    for _event in events.iter() {
-        // get robot energy
-        let data = *VISUALIZER_ENERGY.lock().unwrap() as f32;
-        for mut text in query_hud_text.iter_mut() {
-            text.sections[0].value = format!("Energy: {:?}", data as usize);
-            text.sections[1].value = "/".into();
-            text.sections[2].value = format!("{:?}", 1000);
-        }
-        for mut style in query_style.iter_mut() {
-            style.width = Val::Percent(100. * (data as f32 / 1000.0))
+    // only the newest position matters when several ticks arrive at once
+    let Some(event) = events.iter().last() else {
+        return;
+    };
+    // get robot position
+    let data = event.0.robot_position;
+    // check if robot position is different. if it is, then update on screen
+    if map_info.last_known_robot_position != data {
+        let (transform, mut motion) = query.single_mut();
+        let (old_row, old_col) = map_info.last_known_robot_position;
+        let distance = old_row.abs_diff(data.0) + old_col.abs_diff(data.1);
+        let teleported = event.0.events.iter().any(|event| {
+            matches!(event, Event::Moved(tile, position)
+                if *position == data && matches!(tile.tile_type, TileType::Teleport(_)))
+        });
+        let interval = playback
+            .speed
+            .interval(Duration::from_secs_f32(playback.tick_interval))
+            .as_secs_f32();
+
+        // start from wherever the sprite is, in case the last move hasn't finished
+        motion.from = transform.translation.truncate();
+        motion.to = map_info.grid().robot_translation(data.0, data.1);
+        motion.elapsed = 0.0;
+        if distance > 1 && teleported {
+            motion.kind = MotionKind::Teleport;
+            motion.duration = TELEPORT_SECONDS.min(interval.max(MIN_TWEEN_SECONDS));
+        } else if distance == 1 && interval >= MIN_TWEEN_SECONDS {
+            motion.kind = MotionKind::Walk;
+            motion.duration = interval * 0.9;
+            // robot's x = gui's y and viceversa
+            motion.facing = if data.1 > old_col {
+                Facing::Right
+            } else if data.1 < old_col {
+                Facing::Left
+            } else if data.0 > old_row {
+                Facing::Down
+            } else {
+                Facing::Up
+            };
+        } else {
+            // too fast to animate, or a jump along the timeline
+            motion.kind = MotionKind::Idle;
+            motion.from = motion.to;
         }
+        // save new robot position
+        map_info.last_known_robot_position = data;
     }
+    // send event to update BigBrother camera
+    event_w.send(CameraEvent);
 }
 
-pub fn setup_minimap(mut commands: Commands) {
-    commands.spawn((
-        Camera2dBundle {
-            camera: Camera {
-                // renders after / on top of other cameras
-                order: 2,
-                // set the viewport to a 256x256 square in the top left corner
-                viewport: Some(Viewport {
-                    physical_position: UVec2::new(0, 0),
-                    physical_size: UVec2::new(512, 256),
-                    ..default()
-                }),
-                ..default()
-            },
-            camera_2d: bevy::prelude::Camera2d {
-                clear_color: ClearColorConfig::None,
-                ..default()
-            },
-            projection: OrthographicProjection {
-                scale: 5.0,
-                ..default()
-            },
-            transform: Transform {
-                translation: Vec3::new(0.0, 0.0, 500.0),
-                ..default()
-            },
-            ..default()
-        },
-        MiniCamera,
-    ));
-}
-
-// update day night cycle
-pub fn update_dnc(
-    mut query: Query<(&DncRectangle, &mut Sprite)>,
-    mut events: EventReader<TickEvent>,
+// move the sprite along its current motion and pick the animation frame
+pub fn animate_robot(
+    time: Res<Time>,
+    mut query: Query<
+        (&mut Transform, &mut TextureAtlasSprite, &mut RobotMotion),
+        With<VisualizerRobot>,
+    >,
+    mut event_w: EventWriter<CameraEvent>,
 ) {
-    for _event in events.iter() {
-        let (_dnc_rectangle, mut sprite) = query.single_mut();
-        // get world time
-        let data = VISUALIZER_TIME.lock().unwrap();
-        // calc day night cycle
-        // get time first
-        match data
-            .get_time_of_day_string()
-            .split_once(":")
-            .unwrap()
-            .0
-            .parse::<u8>()
-        {
-            Ok(number) => {
-                let alpha: f32 = match number {
-                    0..=4 => 0.8,
-                    5 => 0.6,
-                    6 => 0.3,
-                    7 => 0.1,
-                    8..=17 => 0.,
-                    18 => 0.2,
-                    19 => 0.4,
-                    20 => 0.6,
-                    21..=23 => 0.8,
-                    _ => 0.0,
+    for (mut transform, mut sprite, mut motion) in query.iter_mut() {
+        let row = motion.facing as usize * ROBOT_WALK_FRAMES;
+        if motion.kind == MotionKind::Idle {
+            if transform.translation.truncate() != motion.to {
+                transform.translation.x = motion.to.x;
+                transform.translation.y = motion.to.y;
+                event_w.send(CameraEvent);
+            }
+            sprite.index = row;
+            sprite.color = Color::WHITE;
+            transform.scale = Vec3::ONE;
+            continue;
+        }
+
+        motion.elapsed += time.delta_seconds();
+        let progress = (motion.elapsed / motion.duration.max(f32::EPSILON)).min(1.0);
+        match motion.kind {
+            MotionKind::Walk => {
+                let position = motion.from.lerp(motion.to, progress);
+                transform.translation.x = position.x;
+                transform.translation.y = position.y;
+                // one full walk cycle per tile
+                sprite.index = row + ((progress * ROBOT_WALK_FRAMES as f32) as usize).min(3);
+            }
+            MotionKind::Teleport => {
+                // shrink and fade out where it was, grow back where it lands
+                let position = if progress < 0.5 {
+                    motion.from
+                } else {
+                    motion.to
                 };
-                sprite.color.set_a(alpha);
+                let fade = (progress * 2.0 - 1.0).abs();
+                transform.translation.x = position.x;
+                transform.translation.y = position.y;
+                transform.scale = Vec3::new(fade, 1.0 + (1.0 - fade), 1.0);
+                sprite.color = Color::rgba(0.4 + 0.6 * fade, 1.0, 1.0, fade);
+                sprite.index = row;
             }
-            _ => {}
+            MotionKind::Idle => {}
+        }
+        event_w.send(CameraEvent);
+        if progress >= 1.0 {
+            motion.kind = MotionKind::Idle;
+            motion.from = motion.to;
         }
     }
 }
 
-// func to change sprite position based on actual robot position
-pub fn update_robot_position(
-    mut query: Query<(&VisualizerRobot, &mut Transform)>,
-    mut map_info: ResMut<MapInfo>,
+// only the newest snapshot has changes that can be trusted to follow the one drawn before it
+pub fn diff_snapshots(
     mut events: EventReader<TickEvent>,
-    mut event_w: EventWriter<CameraEvent>,
+    mut shown_tick: Local<Option<u64>>,
+    mut diff: EventWriter<MapDiffEvent>,
 ) {
-    for _event in events.iter() {
-        // get robot position
-        let data = VISUALIZER_ROBOT_POSITION.lock().unwrap();
-        // check if robot position is different. if it is, then update on screen
-        if map_info.last_known_robot_position != *data {
-            // change robot position in gui
-            // robot's x = gui's y and viceversa
-            let (_player, mut transform) = query.single_mut();
-            println!(
-                "map size: {} - tile_pixel_size {} - x {} - y {} - tile_pixel_offse {}",
-                map_info.size, TILE_PIXEL_SIZE, data.0, data.1, TILE_PIXEL_OFFSET
-            );
-            transform.translation.x = (TILE_PIXEL_SIZE * (data.1 as f32)) + 5.0;
-            transform.translation.y = (map_info.size as f32 * TILE_PIXEL_SIZE)
-                - (TILE_PIXEL_SIZE * (data.0 as f32 + 1.0))
-                + TILE_PIXEL_OFFSET;
-            println!("{} {}", transform.translation.x, transform.translation.y);
-            println!("{:?}", data);
-            // save new robot position
-            map_info.last_known_robot_position = *data;
-        }
-        // send event to update BigBrother camera
-        event_w.send(CameraEvent);
+    for event in events.iter() {
+        let snapshot = &event.0;
+        let follows = shown_tick.is_some_and(|tick| snapshot.tick == tick + 1);
+        diff.send(match &snapshot.changes {
+            Some(changes) if follows => {
+                MapDiffEvent::Cells(changes.iter().map(|(row, col, _)| (*row, *col)).collect())
+            }
+            // first snapshot, a jump along the timeline or a map of a new size
+            _ => MapDiffEvent::All,
+        });
+        *shown_tick = Some(snapshot.tick);
     }
 }
 
 // update tiles
 pub fn update_tilemap(
-    time: ResMut<Time>,
-    mut query: Query<(&TileMap, &mut TileTextureIndex, &mut LastUpdate)>,
-    mut events: EventReader<TickEvent>,
+    mut events: EventReader<MapDiffEvent>,
+    current: Res<CurrentSnapshot>,
+    fog_view: Res<FogView>,
+    ground_truth: Res<GroundTruth>,
+    map_info: Res<MapInfo>,
+    query_layer: Query<&TileStorage, With<TileLayer>>,
+    mut query: Query<&mut TileTextureIndex, With<TileMap>>,
 ) {
-    for _event in events.iter() {
-        // get visualizer map
-        let data = VISUALIZER_MAP.lock().unwrap();
-        // flatten it
-        let flattened = data.clone().unwrap().concat();
-        //println!("{:?}", flattened);
-        let current_time = time.elapsed_seconds_f64();
-        for (index, (_tilemap, mut tile, mut last_update)) in query.iter_mut().enumerate() {
-            if (current_time - last_update.value) > 1.0 {
-                //let data = VISUALIZER_MAP.lock().unwrap();
-                //println!("Global variable value: {:?}", *data);
-                if let Some(flattened_tile) = flattened.get(index) {
-                    if let Some(tile_ref) = flattened_tile.as_ref() {
-                        tile.0 = match tile_ref.tile_type {
-                            TileType::Grass => 0,
-                            TileType::Sand => 1,
-                            TileType::Snow => 2,
-                            TileType::Mountain => 3,
-                            TileType::Teleport(false) => 4,
-                            TileType::Wall => 5,
-                            TileType::Hill => 6,
-                            TileType::Street => 7,
-                            TileType::Lava => 8,
-                            TileType::DeepWater => 9,
-                            TileType::ShallowWater => 10,
-                            _ => 5,
-                        }
-                    } else {
-                        tile.0 = 5
-                    }
-                }
-                last_update.value = current_time;
-            }
+    // get visualizer map
+    let (Some(snapshot), Ok(storage)) = (current.0.as_ref(), query_layer.get_single()) else {
+        return;
+    };
+    // switching between knowledge and ground truth redraws everything at once
+    let Some((cells, _)) = changed_cells(&mut events, fog_view.is_changed(), &snapshot.map) else {
+        return;
+    };
+    for (row, col) in cells {
+        // undiscovered tiles stay as they are under the fog, unless the real world is shown
+        let Some(tile) = snapshot.map[row][col]
+            .as_ref()
+            .or_else(|| ground_truth.tile(*fog_view, row, col))
+        else {
+            continue;
+        };
+        let Some(entity) = map_info
+            .grid()
+            .tile_pos(row, col)
+            .and_then(|pos| storage.get(&pos))
+        else {
+            continue;
+        };
+        if let Ok(mut index) = query.get_mut(entity) {
+            index.0 = tile_texture_index(&tile.tile_type);
         }
     }
 }
 
 // update contents
 pub fn update_contents(
-    time: ResMut<Time>,
-    mut query: Query<(&ContentMap, &mut TileTextureIndex, &mut LastUpdate)>,
-    mut events: EventReader<TickEvent>,
+    mut events: EventReader<MapDiffEvent>,
+    current: Res<CurrentSnapshot>,
+    fog_view: Res<FogView>,
+    ground_truth: Res<GroundTruth>,
+    map_info: Res<MapInfo>,
+    query_layer: Query<&TileStorage, With<ContentLayer>>,
+    mut query: Query<&mut TileTextureIndex, With<ContentMap>>,
 ) {
-    for _event in events.iter() {
-        // get visualizer map
-        let data = VISUALIZER_MAP.lock().unwrap();
-        // flatten it
-        let flattened = data.clone().unwrap().concat();
-        //println!("{:?}", flattened);
-        let current_time = time.elapsed_seconds_f64();
-        for (index, (_contentmap, mut tile, mut last_update)) in query.iter_mut().enumerate() {
-            if (current_time - last_update.value) > 1.0 {
-                if let Some(flattened_content) = flattened.get(index) {
-                    if let Some(content_ref) = flattened_content.as_ref() {
-                        tile.0 = match content_ref.content {
-                            Content::None => 0,
-                            Content::Water(_) => 1,
-                            Content::Scarecrow => 2,
-                            Content::Tree(_) => 3,
-                            Content::Garbage(_) => 4,
-                            Content::Bank(_) => 5,
-                            Content::Crate(_) => 6,
-                            Content::Fish(_) => 7,
-                            Content::Market(_) => 8,
-                            Content::Bush(_) => 9,
-                            Content::Bin(_) => 10,
-                            Content::Coin(_) => 11,
-                            Content::Rock(_) => 12,
-                            Content::JollyBlock(_) => 13,
-                            Content::Fire => 14,
-                            Content::Building => 15,
-                            _ => 0,
-                        }
-                    } else {
-                        tile.0 = 0
-                    }
-                }
-
-                last_update.value = current_time;
-            }
+    // get visualizer map
+    let (Some(snapshot), Ok(storage)) = (current.0.as_ref(), query_layer.get_single()) else {
+        return;
+    };
+    let Some((cells, _)) = changed_cells(&mut events, fog_view.is_changed(), &snapshot.map) else {
+        return;
+    };
+    for (row, col) in cells {
+        let Some(entity) = map_info
+            .grid()
+            .tile_pos(row, col)
+            .and_then(|pos| storage.get(&pos))
+        else {
+            continue;
+        };
+        if let Ok(mut index) = query.get_mut(entity) {
+            index.0 = snapshot.map[row][col]
+                .as_ref()
+                .or_else(|| ground_truth.tile(*fog_view, row, col))
+                .map(|tile| content_texture_index(&tile.content))
+                .unwrap_or(0);
         }
     }
 }
 
+// cells to draw again this frame and whether that is the whole map, None if nothing changed
+pub(crate) fn changed_cells(
+    events: &mut EventReader<MapDiffEvent>,
+    redraw: bool,
+    map: &[Vec<Option<Tile>>],
+) -> Option<(Vec<(usize, usize)>, bool)> {
+    let mut cells = Vec::new();
+    let mut changed = redraw;
+    let mut all = redraw;
+    for event in events.iter() {
+        changed = true;
+        match event {
+            MapDiffEvent::All => all = true,
+            MapDiffEvent::Cells(event_cells) => cells.extend_from_slice(event_cells),
+        }
+    }
+    if !changed {
+        return None;
+    }
+    if all {
+        cells = map
+            .iter()
+            .enumerate()
+            .flat_map(|(row, cells)| (0..cells.len()).map(move |col| (row, col)))
+            .collect();
+    } else {
+        cells.sort_unstable();
+        cells.dedup();
+        // changes of a map that has since changed size
+        cells.retain(|(row, col)| map.get(*row).is_some_and(|cells| *col < cells.len()));
+    }
+    Some((cells, all))
+}
+
 pub fn follow_robot_camera(
-    mut query: Query<(&mut Transform, &mut OrthographicProjection), With<BigBrother>>,
+    mut query: Query<&mut Transform, With<BigBrother>>,
     query_robot: Query<&Transform, (With<VisualizerRobot>, Without<BigBrother>)>,
-    mut query_minimap: Query<
-        &mut Transform,
-        (
-            With<MiniCamera>,
-            Without<VisualizerRobot>,
-            Without<BigBrother>,
-        ),
-    >,
+    control: Res<CameraControl>,
     mut events: EventReader<CameraEvent>,
 ) {
     for _event in events.iter() {
+        // in free mode the camera stays where it was moved to
+        if !control.follow {
+            continue;
+        }
         let robot_transform = query_robot.single();
-        let (mut camera_transform, mut camera_ortho) = query.single_mut();
-
+        let mut camera_transform = query.single_mut();
         let z = camera_transform.translation.z;
         camera_transform.translation.x = robot_transform.translation.x;
         camera_transform.translation.y = robot_transform.translation.y;
-        camera_ortho.scale = 0.6;
         camera_transform.translation.z = z;
-
-        let mut minimap_transform = query_minimap.single_mut();
-
-        let z = minimap_transform.translation.z;
-        minimap_transform.translation.x = robot_transform.translation.x;
-        minimap_transform.translation.y = robot_transform.translation.y;
-        minimap_transform.translation.z = z;
-    }
-}
-
-pub fn camera_movement(
-    time: Res<Time>,
-    keyboard_input: Res<Input<KeyCode>>,
-    mut query: Query<(&mut Transform, &mut OrthographicProjection), With<Camera>>,
-) {
-    for (mut transform, mut ortho) in query.iter_mut() {
-        let mut direction = Vec3::ZERO;
-
-        if keyboard_input.pressed(KeyCode::A) {
-            direction -= Vec3::new(1.0, 0.0, 0.0);
-        }
-
-        if keyboard_input.pressed(KeyCode::D) {
-            direction += Vec3::new(1.0, 0.0, 0.0);
-        }
-
-        if keyboard_input.pressed(KeyCode::W) {
-            direction += Vec3::new(0.0, 1.0, 0.0);
-        }
-
-        if keyboard_input.pressed(KeyCode::S) {
-            direction -= Vec3::new(0.0, 1.0, 0.0);
-        }
-
-        if keyboard_input.pressed(KeyCode::Z) {
-            ortho.scale += 0.1;
-        }
-
-        if keyboard_input.pressed(KeyCode::X) {
-            ortho.scale -= 0.1;
-        }
-
-        /*if ortho.scale < 0.5 {
-            ortho.scale = 0.5;
-        }*/
-
-        let z = transform.translation.z;
-        transform.translation += time.delta_seconds() * direction * 500.;
-        // Important! We need to restore the Z values when moving the camera around.
-        // Bevy has a specific camera setup and this can mess with how our layers are shown.
-        transform.translation.z = z;
     }
 }
@@ -0,0 +1,235 @@
+use crate::coords::{MapGrid, TILE_PIXEL_SIZE};
+use crate::entities::{OverlayLayer, OverlayMap, OverlayText};
+use crate::events::MapDiffEvent;
+use crate::resources::{FogView, GroundTruth, MapInfo, OverlayMode, TerrainOverlay};
+use crate::snapshot::CurrentSnapshot;
+use crate::systems::changed_cells;
+use crate::world::Tile;
+
+use bevy::input::Input;
+use bevy::prelude::{
+    default, Color, Commands, DetectChanges, EventReader, Gizmos, KeyCode, Local, Query, Res,
+    ResMut, Vec2, With,
+};
+use bevy::text::{Text, TextStyle};
+use bevy::ui::node_bundles::TextBundle;
+use bevy::ui::{PositionType, Style, Val};
+
+use bevy_ecs_tilemap::prelude::{TileColor, TileStorage, TileVisible};
+
+const OVERLAY_ALPHA: f32 = 0.55;
+// contour lines split the elevation range in about this many bands
+const CONTOUR_BANDS: usize = 10;
+
+// what a cell of the overlay shows
+#[derive(Clone, Copy, PartialEq)]
+enum CellValue {
+    Hidden,
+    // the robot can't walk there
+    Blocked,
+    Value(usize),
+}
+
+pub fn setup_overlay_text(mut commands: Commands) {
+    commands.spawn((
+        TextBundle {
+            text: Text::from_section(
+                "",
+                TextStyle {
+                    font_size: 20.0,
+                    color: Color::WHITE,
+                    ..default()
+                },
+            ),
+            style: Style {
+                position_type: PositionType::Absolute,
+                // under the discovered percentage
+                top: Val::Px(82.),
+                left: Val::Percent(42.),
+                ..default()
+            },
+            ..default()
+        },
+        OverlayText,
+    ));
+}
+
+// e: elevation, contour lines, movement cost, energy cost, off
+pub fn overlay_keyboard(keyboard_input: Res<Input<KeyCode>>, mut overlay: ResMut<TerrainOverlay>) {
+    if keyboard_input.just_pressed(KeyCode::E) {
+        overlay.mode = overlay.mode.next();
+    }
+}
+
+pub fn update_overlay(
+    mut events: EventReader<MapDiffEvent>,
+    current: Res<CurrentSnapshot>,
+    fog_view: Res<FogView>,
+    ground_truth: Res<GroundTruth>,
+    map_info: Res<MapInfo>,
+    mut overlay: ResMut<TerrainOverlay>,
+    mut shown_mode: Local<Option<OverlayMode>>,
+    query_layer: Query<&TileStorage, With<OverlayLayer>>,
+    mut query: Query<(&mut TileVisible, &mut TileColor), With<OverlayMap>>,
+    mut query_text: Query<&mut Text, With<OverlayText>>,
+) {
+    let (Some(snapshot), Ok(storage)) = (current.0.as_ref(), query_layer.get_single()) else {
+        return;
+    };
+    let mode = overlay.mode;
+    let redraw = *shown_mode != Some(mode) || fog_view.is_changed();
+    *shown_mode = Some(mode);
+    let Some((mut cells, mut all)) = changed_cells(&mut events, redraw, &snapshot.map) else {
+        return;
+    };
+
+    let grid = map_info.grid();
+    let tile_at = |row: usize, col: usize| {
+        snapshot
+            .map
+            .get(row)
+            .and_then(|cells| cells.get(col))
+            .and_then(|cell| cell.as_ref())
+            .or_else(|| ground_truth.tile(*fog_view, row, col))
+    };
+    let value_at = |row: usize, col: usize| cell_value(mode, &grid, &tile_at, row, col);
+
+    // colors are spread over the values on screen, a new range redraws everything
+    let range = grid
+        .cells()
+        .filter_map(|(row, col)| match value_at(row, col) {
+            CellValue::Value(value) => Some(value),
+            _ => None,
+        })
+        .fold(None, |range: Option<(usize, usize)>, value| {
+            Some(range.map_or((value, value), |(min, max)| {
+                (min.min(value), max.max(value))
+            }))
+        });
+    if range != overlay.range {
+        overlay.range = range;
+        all = true;
+    }
+    if all {
+        cells = grid.cells().collect();
+    } else {
+        // the energy cost of a tile depends on the tiles around it
+        let neighbours: Vec<(usize, usize)> = cells
+            .iter()
+            .flat_map(|(row, col)| neighbours(&grid, *row, *col))
+            .collect();
+        cells.extend(neighbours);
+        cells.sort_unstable();
+        cells.dedup();
+    }
+
+    let (min, max) = range.unwrap_or((0, 0));
+    let span = (max - min).max(1) as f32;
+    for (row, col) in cells {
+        let Some(entity) = grid.tile_pos(row, col).and_then(|pos| storage.get(&pos)) else {
+            continue;
+        };
+        let Ok((mut visible, mut color)) = query.get_mut(entity) else {
+            continue;
+        };
+        let value = match mode {
+            // contour lines are drawn on their own
+            OverlayMode::Off | OverlayMode::Contours => CellValue::Hidden,
+            _ => value_at(row, col),
+        };
+        match value {
+            CellValue::Hidden => visible.0 = false,
+            CellValue::Blocked => {
+                visible.0 = true;
+                color.0 = Color::rgba(0.05, 0.05, 0.05, 0.75);
+            }
+            CellValue::Value(value) => {
+                // blue for the lowest values, red for the highest
+                let t = (value - min) as f32 / span;
+                visible.0 = true;
+                color.0 = Color::hsla((1.0 - t) * 240.0, 0.9, 0.5, OVERLAY_ALPHA);
+            }
+        }
+    }
+
+    overlay.contours.clear();
+    if mode == OverlayMode::Contours {
+        let step = ((max - min) / CONTOUR_BANDS).max(1);
+        let band = |row: usize, col: usize| tile_at(row, col).map(|tile| tile.elevation / step);
+        let half = TILE_PIXEL_SIZE / 2.0;
+        for (row, col) in grid.cells() {
+            let Some(here) = band(row, col) else {
+                continue;
+            };
+            let center = grid.cell_center(row, col);
+            // edges shared with the tile to the right and the one below
+            if band(row, col + 1).is_some_and(|right| right != here) {
+                overlay.contours.push((
+                    center + Vec2::new(half, -half),
+                    center + Vec2::new(half, half),
+                ));
+            }
+            if band(row + 1, col).is_some_and(|below| below != here) {
+                overlay.contours.push((
+                    center + Vec2::new(-half, -half),
+                    center + Vec2::new(half, -half),
+                ));
+            }
+        }
+    }
+
+    for mut text in query_text.iter_mut() {
+        text.sections[0].value = match (mode, range) {
+            (OverlayMode::Off, _) => String::new(),
+            (_, Some((min, max))) => format!("{}: {} to {}", mode.label(), min, max),
+            (_, None) => mode.label().to_string(),
+        };
+    }
+}
+
+pub fn draw_contours(overlay: Res<TerrainOverlay>, mut gizmos: Gizmos) {
+    for (start, end) in overlay.contours.iter() {
+        gizmos.line_2d(*start, *end, Color::rgba(0.25, 0.1, 0.0, 0.9));
+    }
+}
+
+fn cell_value<'a>(
+    mode: OverlayMode,
+    grid: &MapGrid,
+    tile_at: &impl Fn(usize, usize) -> Option<&'a Tile>,
+    row: usize,
+    col: usize,
+) -> CellValue {
+    let Some(tile) = tile_at(row, col) else {
+        return CellValue::Hidden;
+    };
+    let properties = tile.tile_type.properties();
+    match mode {
+        OverlayMode::Off => CellValue::Hidden,
+        OverlayMode::Elevation | OverlayMode::Contours => CellValue::Value(tile.elevation),
+        OverlayMode::MovementCost if !properties.walk() => CellValue::Blocked,
+        OverlayMode::MovementCost => CellValue::Value(properties.cost()),
+        OverlayMode::EnergyCost if !properties.walk() => CellValue::Blocked,
+        // stepping onto the tile from the neighbour the climb is steepest from,
+        // climbing costs the square of the elevation difference
+        OverlayMode::EnergyCost => {
+            let climb = neighbours(grid, row, col)
+                .filter_map(|(row, col)| tile_at(row, col))
+                .map(|neighbour| tile.elevation.saturating_sub(neighbour.elevation))
+                .max()
+                .unwrap_or(0);
+            CellValue::Value(properties.cost() + climb.pow(2))
+        }
+    }
+}
+
+fn neighbours(grid: &MapGrid, row: usize, col: usize) -> impl Iterator<Item = (usize, usize)> {
+    let grid = *grid;
+    [(0, 1), (2, 1), (1, 0), (1, 2)]
+        .into_iter()
+        .filter_map(move |(row_offset, col_offset)| {
+            let row = (row + row_offset).checked_sub(1)?;
+            let col = (col + col_offset).checked_sub(1)?;
+            grid.contains(row, col).then_some((row, col))
+        })
+}
@@ -0,0 +1,200 @@
+use crate::cli::{Args, MinimapCorner};
+use crate::components::PlaybackButton;
+use crate::entities::PlaybackText;
+use crate::history::SnapshotHistory;
+use crate::resources::{PlaybackSpeed, PlaybackState, Timeline};
+use crate::simulation::{Simulation, SimulationCommand};
+use crate::snapshot::CurrentSnapshot;
+use crate::systems::minimap::minimap_room;
+
+use bevy::input::Input;
+use bevy::prelude::{
+    default, BuildChildren, ButtonBundle, Changed, Color, Commands, DetectChanges, KeyCode,
+    NodeBundle, Query, Res, ResMut, With,
+};
+use bevy::text::{Text, TextStyle};
+use bevy::ui::node_bundles::TextBundle;
+use bevy::ui::{AlignItems, FlexDirection, Interaction, JustifyContent, PositionType, Style};
+use bevy::ui::{UiRect, Val};
+
+pub fn setup_playback_controls(mut commands: Commands, args: Res<Args>) {
+    let layout = commands
+        .spawn(NodeBundle {
+            style: Style {
+                position_type: PositionType::Absolute,
+                // below the minimap when it is in the same corner
+                top: Val::Px(10. + minimap_room(&args, MinimapCorner::TopRight)),
+                right: Val::Px(10.),
+                flex_direction: FlexDirection::Column,
+                align_items: AlignItems::FlexEnd,
+                row_gap: Val::Px(4.),
+                ..default()
+            },
+            ..default()
+        })
+        .id();
+    let status = commands
+        .spawn((
+            TextBundle {
+                text: Text::from_section(
+                    "",
+                    TextStyle {
+                        font_size: 24.0,
+                        color: Color::WHITE,
+                        ..default()
+                    },
+                ),
+                ..default()
+            },
+            PlaybackText,
+        ))
+        .id();
+    let buttons_row = commands
+        .spawn(NodeBundle {
+            style: Style {
+                flex_direction: FlexDirection::Row,
+                column_gap: Val::Px(4.),
+                ..default()
+            },
+            ..default()
+        })
+        .id();
+    commands
+        .entity(layout)
+        .push_children(&[status, buttons_row]);
+
+    for (button, label) in [
+        (PlaybackButton::TogglePause, "Play/Pause"),
+        (PlaybackButton::Step, "Step"),
+        (PlaybackButton::Slower, "-"),
+        (PlaybackButton::Faster, "+"),
+        (PlaybackButton::Max, "Max"),
+    ] {
+        let button_entity = commands
+            .spawn((
+                ButtonBundle {
+                    style: Style {
+                        padding: UiRect::axes(Val::Px(8.), Val::Px(4.)),
+                        justify_content: JustifyContent::Center,
+                        align_items: AlignItems::Center,
+                        ..default()
+                    },
+                    background_color: Color::rgba(0.1, 0.1, 0.1, 0.8).into(),
+                    ..default()
+                },
+                button,
+            ))
+            .with_children(|parent| {
+                parent.spawn(TextBundle::from_section(
+                    label,
+                    TextStyle {
+                        font_size: 20.0,
+                        color: Color::WHITE,
+                        ..default()
+                    },
+                ));
+            })
+            .id();
+        commands.entity(buttons_row).add_child(button_entity);
+    }
+}
+
+// space: pause/resume, period: step, +/-: speed, 0: as fast as possible
+pub fn playback_keyboard(
+    keyboard_input: Res<Input<KeyCode>>,
+    mut playback: ResMut<PlaybackState>,
+    mut timeline: ResMut<Timeline>,
+    history: Res<SnapshotHistory>,
+    simulation: Res<Simulation>,
+) {
+    let action = if keyboard_input.just_pressed(KeyCode::Space) {
+        PlaybackButton::TogglePause
+    } else if keyboard_input.just_pressed(KeyCode::Period) {
+        PlaybackButton::Step
+    } else if keyboard_input.any_just_pressed([KeyCode::Minus, KeyCode::NumpadSubtract]) {
+        PlaybackButton::Slower
+    } else if keyboard_input.any_just_pressed([KeyCode::Equals, KeyCode::NumpadAdd]) {
+        PlaybackButton::Faster
+    } else if keyboard_input.just_pressed(KeyCode::Key0) {
+        PlaybackButton::Max
+    } else {
+        return;
+    };
+    apply_playback(action, &mut playback, &mut timeline, &history, &simulation);
+}
+
+pub fn playback_buttons(
+    query: Query<(&Interaction, &PlaybackButton), Changed<Interaction>>,
+    mut playback: ResMut<PlaybackState>,
+    mut timeline: ResMut<Timeline>,
+    history: Res<SnapshotHistory>,
+    simulation: Res<Simulation>,
+) {
+    for (interaction, button) in query.iter() {
+        if *interaction == Interaction::Pressed {
+            apply_playback(*button, &mut playback, &mut timeline, &history, &simulation);
+        }
+    }
+}
+
+fn apply_playback(
+    action: PlaybackButton,
+    playback: &mut PlaybackState,
+    timeline: &mut Timeline,
+    history: &SnapshotHistory,
+    simulation: &Simulation,
+) {
+    match action {
+        PlaybackButton::TogglePause => {
+            playback.paused = !playback.paused;
+            simulation.send(if playback.paused {
+                SimulationCommand::Pause
+            } else {
+                SimulationCommand::Resume
+            });
+        }
+        PlaybackButton::Step if timeline.viewing.is_some() => {
+            // looking at the history, step through it instead of the robot
+            playback.paused = true;
+            simulation.send(SimulationCommand::Pause);
+            timeline.step(history, 1);
+        }
+        PlaybackButton::Step => {
+            playback.paused = true;
+            simulation.send(SimulationCommand::Step);
+        }
+        PlaybackButton::Slower | PlaybackButton::Faster | PlaybackButton::Max => {
+            playback.speed = match action {
+                PlaybackButton::Slower => playback.speed.slower(),
+                PlaybackButton::Faster => playback.speed.faster(),
+                // pressing max again goes back to normal speed
+                _ if playback.speed == PlaybackSpeed::Max => PlaybackSpeed::default(),
+                _ => PlaybackSpeed::Max,
+            };
+            simulation.send(SimulationCommand::SetSpeed(playback.speed));
+        }
+    }
+}
+
+pub fn update_playback_text(
+    playback: Res<PlaybackState>,
+    current: Res<CurrentSnapshot>,
+    mut query: Query<&mut Text, With<PlaybackText>>,
+) {
+    if !playback.is_changed() && !current.is_changed() {
+        return;
+    }
+    let tick = current
+        .0
+        .as_ref()
+        .map(|snapshot| snapshot.tick)
+        .unwrap_or(0);
+    for mut text in query.iter_mut() {
+        text.sections[0].value = format!(
+            "{}  {}  tick {}",
+            if playback.paused { "PAUSED" } else { "PLAYING" },
+            playback.speed.label(),
+            tick
+        );
+    }
+}
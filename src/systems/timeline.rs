@@ -0,0 +1,203 @@
+use crate::entities::{TimelineFill, TimelineSlider, TimelineText};
+use crate::events::TickEvent;
+use crate::history::SnapshotHistory;
+use crate::resources::{PlaybackState, Timeline};
+use crate::snapshot::CurrentSnapshot;
+
+use bevy::input::Input;
+use bevy::prelude::{
+    default, BuildChildren, ButtonBundle, Color, Commands, DetectChanges, DetectChangesMut,
+    EventWriter, GlobalTransform, KeyCode, NodeBundle, Query, Res, ResMut, Time, With, Without,
+};
+use bevy::text::{Text, TextStyle};
+use bevy::ui::node_bundles::TextBundle;
+use bevy::ui::{Interaction, Node, PositionType, Style, Val};
+use bevy::window::{PrimaryWindow, Window};
+
+use std::time::Duration;
+
+pub fn setup_timeline(mut commands: Commands) {
+    let label = commands
+        .spawn((
+            TextBundle {
+                text: Text::from_section(
+                    "",
+                    TextStyle {
+                        font_size: 20.0,
+                        color: Color::WHITE,
+                        ..default()
+                    },
+                ),
+                style: Style {
+                    position_type: PositionType::Absolute,
+                    bottom: Val::Px(16.),
+                    left: Val::Px(0.),
+                    ..default()
+                },
+                ..default()
+            },
+            TimelineText,
+        ))
+        .id();
+    let fill = commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    width: Val::Percent(100.),
+                    height: Val::Percent(100.),
+                    ..default()
+                },
+                background_color: Color::rgb(0.9, 0.6, 0.1).into(),
+                ..default()
+            },
+            TimelineFill,
+        ))
+        .id();
+    commands
+        .spawn((
+            ButtonBundle {
+                style: Style {
+                    position_type: PositionType::Absolute,
+                    bottom: Val::Vh(6.),
+                    left: Val::Vw(2.5),
+                    width: Val::Vw(95.),
+                    height: Val::Px(12.),
+                    ..default()
+                },
+                background_color: Color::rgba(0.1, 0.1, 0.1, 0.8).into(),
+                ..default()
+            },
+            TimelineSlider,
+        ))
+        .push_children(&[fill, label]);
+}
+
+// left/right: one tick (ten with shift), home: oldest tick, end: back to live
+pub fn timeline_keyboard(
+    keyboard_input: Res<Input<KeyCode>>,
+    mut timeline: ResMut<Timeline>,
+    history: Res<SnapshotHistory>,
+) {
+    let amount = if keyboard_input.any_pressed([KeyCode::ShiftLeft, KeyCode::ShiftRight]) {
+        10
+    } else {
+        1
+    };
+    if keyboard_input.just_pressed(KeyCode::Left) {
+        timeline.step(&history, -amount);
+    } else if keyboard_input.just_pressed(KeyCode::Right) {
+        timeline.step(&history, amount);
+    } else if keyboard_input.just_pressed(KeyCode::Home) {
+        timeline.jump_to(&history, 0);
+    } else if keyboard_input.just_pressed(KeyCode::End) {
+        timeline.viewing = None;
+    }
+}
+
+// click or drag on the bar to jump to that point of the history
+pub fn timeline_slider(
+    query: Query<(&Interaction, &Node, &GlobalTransform), With<TimelineSlider>>,
+    windows: Query<&Window, With<PrimaryWindow>>,
+    mut timeline: ResMut<Timeline>,
+    history: Res<SnapshotHistory>,
+) {
+    let Ok(window) = windows.get_single() else {
+        return;
+    };
+    let Some(cursor) = window.cursor_position() else {
+        return;
+    };
+    for (interaction, node, transform) in query.iter() {
+        if *interaction != Interaction::Pressed || history.is_empty() {
+            continue;
+        }
+        let width = node.size().x.max(1.0);
+        let left = transform.translation().x - width / 2.0;
+        let fraction = ((cursor.x - left) / width).clamp(0.0, 1.0);
+        let index = (fraction * (history.len() - 1) as f32).round() as usize;
+        if Some(index) != timeline.index(&history) {
+            timeline.jump_to(&history, index);
+        }
+    }
+}
+
+// while playing an older part of the history, move through it at the playback speed
+pub fn timeline_playback(
+    time: Res<Time>,
+    playback: Res<PlaybackState>,
+    mut timeline: ResMut<Timeline>,
+    history: Res<SnapshotHistory>,
+) {
+    if timeline.viewing.is_none() || playback.paused {
+        return;
+    }
+    let interval = playback
+        .speed
+        .interval(Duration::from_secs_f32(playback.tick_interval))
+        .as_secs_f32();
+    // bypass change detection until the cursor actually moves
+    timeline.bypass_change_detection().elapsed += time.delta_seconds();
+    if timeline.elapsed >= interval {
+        timeline.step(&history, 1);
+    }
+}
+
+// show the snapshot under the cursor whenever it moves
+pub fn show_timeline_snapshot(
+    timeline: Res<Timeline>,
+    history: Res<SnapshotHistory>,
+    mut current: ResMut<CurrentSnapshot>,
+    mut event: EventWriter<TickEvent>,
+) {
+    if !timeline.is_changed() {
+        return;
+    }
+    let Some(snapshot) = timeline
+        .index(&history)
+        .and_then(|index| history.snapshot_at(index))
+    else {
+        return;
+    };
+    let already_shown = current
+        .0
+        .as_ref()
+        .map(|shown| shown.tick == snapshot.tick)
+        .unwrap_or(false);
+    if !already_shown {
+        current.0 = Some(snapshot.clone());
+        event.send(TickEvent(snapshot));
+    }
+}
+
+pub fn update_timeline_bar(
+    timeline: Res<Timeline>,
+    history: Res<SnapshotHistory>,
+    mut query_fill: Query<&mut Style, With<TimelineFill>>,
+    mut query_text: Query<&mut Text, (With<TimelineText>, Without<TimelineFill>)>,
+) {
+    if !timeline.is_changed() && !history.is_changed() {
+        return;
+    }
+    let (Some(index), Some(newest)) = (timeline.index(&history), history.iter().next_back()) else {
+        return;
+    };
+    let fraction = if history.len() > 1 {
+        index as f32 / (history.len() - 1) as f32
+    } else {
+        1.0
+    };
+    for mut style in query_fill.iter_mut() {
+        style.width = Val::Percent(100. * fraction);
+    }
+    let shown = history.iter().nth(index).map(|snapshot| snapshot.tick);
+    for mut text in query_text.iter_mut() {
+        text.sections[0].value = match timeline.viewing {
+            Some(_) => format!(
+                "tick {} / {}  (home/end, arrows)",
+                shown.unwrap_or(0),
+                newest.tick
+            ),
+            None => format!("tick {}  live", newest.tick),
+        };
+    }
+}
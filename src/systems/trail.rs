@@ -0,0 +1,123 @@
+use crate::entities::BigBrother;
+use crate::history::SnapshotHistory;
+use crate::resources::{MapInfo, RobotTrail, TrailMode, TrailPoint};
+use crate::snapshot::CurrentSnapshot;
+
+use bevy::input::Input;
+use bevy::math::Rect;
+use bevy::prelude::{
+    Color, DetectChanges, Gizmos, KeyCode, Query, Res, ResMut, Transform, Vec2, With,
+};
+use bevy::render::camera::OrthographicProjection;
+use bevy::window::{PrimaryWindow, Window};
+
+// positions in the short fading trail
+const RECENT_POINTS: usize = 40;
+
+// remember where the robot was in every tick added to the history since last frame,
+// and forget it for the ticks the history dropped
+pub fn collect_trail(history: Res<SnapshotHistory>, mut trail: ResMut<RobotTrail>) {
+    if !history.is_changed() {
+        return;
+    }
+    let last_tick = trail.last_tick;
+    let new: Vec<TrailPoint> = history
+        .iter()
+        .rev()
+        .take_while(|snapshot| last_tick.is_none_or(|last| snapshot.tick > last))
+        .map(|snapshot| TrailPoint {
+            tick: snapshot.tick,
+            position: snapshot.robot_position,
+            energy: snapshot.energy,
+        })
+        .collect();
+    for point in new.into_iter().rev() {
+        trail.last_tick = Some(point.tick);
+        trail.max_energy = trail.max_energy.max(point.energy);
+        // standing still doesn't add anything to the path
+        if trail.points.back().map(|last| last.position) != Some(point.position) {
+            trail.points.push_back(point);
+        }
+    }
+    if let Some(oldest) = history.iter().next().map(|snapshot| snapshot.tick) {
+        // the point the robot stood on at the oldest tick stays
+        while trail.points.len() > 1 && trail.points[1].tick <= oldest {
+            trail.points.pop_front();
+        }
+    }
+}
+
+// t: short trail, full path by age, full path by energy, off
+pub fn trail_keyboard(keyboard_input: Res<Input<KeyCode>>, mut trail: ResMut<RobotTrail>) {
+    if keyboard_input.just_pressed(KeyCode::T) {
+        trail.mode = trail.mode.next();
+    }
+}
+
+pub fn draw_trail(
+    trail: Res<RobotTrail>,
+    current: Res<CurrentSnapshot>,
+    map_info: Res<MapInfo>,
+    query_camera: Query<(&Transform, &OrthographicProjection), With<BigBrother>>,
+    windows: Query<&Window, With<PrimaryWindow>>,
+    mut gizmos: Gizmos,
+) {
+    if trail.mode == TrailMode::Off {
+        return;
+    }
+    let Some(tick) = current.0.as_ref().map(|snapshot| snapshot.tick) else {
+        return;
+    };
+    let (Ok((camera, projection)), Ok(window)) = (query_camera.get_single(), windows.get_single())
+    else {
+        return;
+    };
+    // only the path up to the tick on screen
+    let end = trail.points.partition_point(|point| point.tick <= tick);
+    let start = match trail.mode {
+        TrailMode::Recent => end.saturating_sub(RECENT_POINTS),
+        _ => 0,
+    };
+    let len = end - start;
+    let (Some(first), Some(last)) = (
+        trail.points.get(start),
+        end.checked_sub(1).and_then(|last| trail.points.get(last)),
+    ) else {
+        return;
+    };
+    let grid = map_info.grid();
+    let span = (last.tick - first.tick).max(1) as f32;
+    // segments off screen are skipped, the full path can be long
+    let half_size = Vec2::new(window.width(), window.height()) * projection.scale / 2.0;
+    let visible = Rect::from_center_half_size(camera.translation.truncate(), half_size);
+
+    let points = trail.points.range(start..end);
+    for (index, (from, to)) in points.clone().zip(points.skip(1)).enumerate() {
+        let from_center = grid.cell_center(from.position.0, from.position.1);
+        let to_center = grid.cell_center(to.position.0, to.position.1);
+        if !visible.contains(from_center) && !visible.contains(to_center) {
+            continue;
+        }
+        let color = match trail.mode {
+            TrailMode::Recent => {
+                let age = (len - 1 - index) as f32 / len as f32;
+                Color::rgba(1.0, 0.9, 0.2, 1.0 - age)
+            }
+            TrailMode::ByAge => {
+                let fraction = (to.tick - first.tick) as f32 / span;
+                Color::rgba(
+                    0.2 + 0.8 * fraction,
+                    0.4 + 0.5 * fraction,
+                    1.0 - 0.8 * fraction,
+                    0.9,
+                )
+            }
+            TrailMode::ByEnergy => {
+                let fraction = to.energy as f32 / trail.max_energy.max(1) as f32;
+                Color::rgba(1.0 - fraction, fraction, 0.2, 0.9)
+            }
+            TrailMode::Off => continue,
+        };
+        gizmos.line_2d(from_center, to_center, color);
+    }
+}
@@ -0,0 +1,281 @@
+use crate::components::WeatherParticle;
+use crate::entities::{BigBrother, WeatherIcon, WeatherOverlay, WeatherText};
+use crate::history::SnapshotHistory;
+use crate::resources::{MapInfo, RandomSource};
+use crate::snapshot::CurrentSnapshot;
+use crate::tiles::{weather_icon_index, weather_name, WEATHER_ICONS};
+use crate::world::WeatherType;
+
+use bevy::asset::{AssetServer, Assets};
+use bevy::math::Vec3;
+use bevy::prelude::{
+    default, BuildChildren, Color, Commands, DespawnRecursiveExt, DetectChanges, Entity, Local,
+    NodeBundle, Query, Res, ResMut, SpriteBundle, Time, Transform, Vec2, With, Without,
+};
+use bevy::render::camera::OrthographicProjection;
+use bevy::sprite::{Sprite, TextureAtlas};
+use bevy::text::{Text, TextStyle};
+use bevy::ui::node_bundles::{AtlasImageBundle, TextBundle};
+use bevy::ui::{AlignItems, FlexDirection, PositionType, Style, UiRect, UiTextureAtlasImage, Val};
+use bevy::window::{PrimaryWindow, Window};
+
+use rand::Rng;
+
+// particles alive at once, the oldest keep falling until they leave the screen
+const MAX_PARTICLES: usize = 1500;
+// recorded weather changes listed after the current one
+const UPCOMING_LENGTH: usize = 3;
+
+struct ParticleSpec {
+    // particles per second for every 1000 world pixels of screen width
+    rate: f32,
+    velocity: Vec2,
+    // random extra horizontal speed
+    drift: f32,
+    size: Vec2,
+    color: Color,
+}
+
+fn particles(weather: &WeatherType) -> Option<ParticleSpec> {
+    match weather {
+        WeatherType::Rainy => Some(ParticleSpec {
+            rate: 250.,
+            velocity: Vec2::new(-80., -700.),
+            drift: 20.,
+            size: Vec2::new(2., 14.),
+            color: Color::rgba(0.6, 0.7, 1.0, 0.6),
+        }),
+        WeatherType::TropicalMonsoon => Some(ParticleSpec {
+            rate: 600.,
+            velocity: Vec2::new(-250., -1000.),
+            drift: 60.,
+            size: Vec2::new(2., 20.),
+            color: Color::rgba(0.5, 0.8, 0.8, 0.6),
+        }),
+        WeatherType::TrentinoSnow => Some(ParticleSpec {
+            rate: 120.,
+            velocity: Vec2::new(0., -90.),
+            drift: 40.,
+            size: Vec2::new(5., 5.),
+            color: Color::rgba(1.0, 1.0, 1.0, 0.9),
+        }),
+        WeatherType::Sunny | WeatherType::Foggy => None,
+    }
+}
+
+// color laid over the world for each weather
+fn tint(weather: &WeatherType) -> Color {
+    match weather {
+        WeatherType::Sunny => Color::rgba(1.0, 0.85, 0.4, 0.08),
+        WeatherType::Rainy => Color::rgba(0.3, 0.35, 0.5, 0.2),
+        WeatherType::Foggy => Color::rgba(0.8, 0.8, 0.82, 0.5),
+        WeatherType::TropicalMonsoon => Color::rgba(0.1, 0.4, 0.35, 0.25),
+        WeatherType::TrentinoSnow => Color::rgba(0.85, 0.9, 1.0, 0.2),
+    }
+}
+
+pub fn setup_weather(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    mut atlases: ResMut<Assets<TextureAtlas>>,
+    map_info: Res<MapInfo>,
+) {
+    // tint over the map, between the robot and the day/night rectangle
+    let grid = map_info.grid();
+    commands.spawn((
+        WeatherOverlay,
+        SpriteBundle {
+            sprite: Sprite {
+                color: Color::NONE,
+                custom_size: Some(grid.pixel_size()),
+                ..default()
+            },
+            transform: Transform::from_translation(grid.center().extend(45.)),
+            ..default()
+        },
+    ));
+
+    let atlas = TextureAtlas::from_grid(
+        asset_server.load(WEATHER_ICONS),
+        Vec2::new(32.0, 32.0),
+        5,
+        1,
+        None,
+        None,
+    );
+    let icon = commands
+        .spawn((
+            AtlasImageBundle {
+                style: Style {
+                    width: Val::Px(32.),
+                    height: Val::Px(32.),
+                    ..default()
+                },
+                texture_atlas: atlases.add(atlas),
+                ..default()
+            },
+            WeatherIcon,
+        ))
+        .id();
+    let text = commands
+        .spawn((
+            TextBundle::from_section(
+                "",
+                TextStyle {
+                    font_size: 20.0,
+                    color: Color::WHITE,
+                    ..default()
+                },
+            ),
+            WeatherText,
+        ))
+        .id();
+    commands
+        .spawn(NodeBundle {
+            style: Style {
+                position_type: PositionType::Absolute,
+                top: Val::Px(10.),
+                left: Val::Percent(42.),
+                flex_direction: FlexDirection::Row,
+                align_items: AlignItems::Center,
+                column_gap: Val::Px(8.),
+                padding: UiRect::all(Val::Px(4.)),
+                ..default()
+            },
+            background_color: Color::rgba(0.0, 0.0, 0.0, 0.6).into(),
+            ..default()
+        })
+        .push_children(&[icon, text]);
+}
+
+// icon, overlay and upcoming weather for the snapshot on screen
+pub fn update_weather(
+    current: Res<CurrentSnapshot>,
+    history: Res<SnapshotHistory>,
+    mut query_overlay: Query<&mut Sprite, With<WeatherOverlay>>,
+    mut query_icon: Query<&mut UiTextureAtlasImage, With<WeatherIcon>>,
+    mut query_text: Query<&mut Text, With<WeatherText>>,
+) {
+    if !current.is_changed() {
+        return;
+    }
+    let Some(snapshot) = current.0.as_ref() else {
+        return;
+    };
+    let weather = snapshot.environment.get_weather_condition();
+    for mut sprite in query_overlay.iter_mut() {
+        sprite.color = tint(&weather);
+    }
+    for mut icon in query_icon.iter_mut() {
+        icon.index = weather_icon_index(&weather);
+    }
+
+    // nothing is known about the future of a live run, but when looking back the
+    // history has the weather changes recorded after the tick on screen
+    let mut upcoming = Vec::new();
+    let mut last = weather_icon_index(&weather);
+    if let Some(index) = history.index_of(snapshot.tick) {
+        for later in history.iter().skip(index + 1) {
+            let later_weather = later.environment.get_weather_condition();
+            if weather_icon_index(&later_weather) != last {
+                last = weather_icon_index(&later_weather);
+                upcoming.push(format!(
+                    "{} at {}",
+                    weather_name(&later_weather),
+                    later.environment.get_time_of_day_string()
+                ));
+                if upcoming.len() == UPCOMING_LENGTH {
+                    break;
+                }
+            }
+        }
+    }
+    for mut text in query_text.iter_mut() {
+        text.sections[0].value = if upcoming.is_empty() {
+            weather_name(&weather).to_string()
+        } else {
+            format!(
+                "{}  upcoming: {}",
+                weather_name(&weather),
+                upcoming.join(", ")
+            )
+        };
+    }
+}
+
+// rain and snow falling over the part of the world the camera sees
+pub fn spawn_weather_particles(
+    mut commands: Commands,
+    time: Res<Time>,
+    current: Res<CurrentSnapshot>,
+    mut random: ResMut<RandomSource>,
+    mut pending: Local<f32>,
+    query_camera: Query<(&Transform, &OrthographicProjection), With<BigBrother>>,
+    query_particles: Query<(), With<WeatherParticle>>,
+    windows: Query<&Window, With<PrimaryWindow>>,
+) {
+    let Some(spec) = current
+        .0
+        .as_ref()
+        .and_then(|snapshot| particles(&snapshot.environment.get_weather_condition()))
+    else {
+        *pending = 0.0;
+        return;
+    };
+    let (Ok((camera, projection)), Ok(window)) = (query_camera.get_single(), windows.get_single())
+    else {
+        return;
+    };
+    let half_width = window.width() * projection.scale / 2.0;
+    let half_height = window.height() * projection.scale / 2.0;
+
+    *pending += spec.rate * (half_width * 2.0 / 1000.0) * time.delta_seconds();
+    let room = MAX_PARTICLES.saturating_sub(query_particles.iter().count());
+    let count = (*pending as usize).min(room);
+    *pending -= *pending as usize as f32;
+
+    for _ in 0..count {
+        let rng = &mut random.0;
+        // start a bit to the right so slanted rain still covers the screen
+        let x = camera.translation.x + rng.gen_range(-half_width..half_width * 1.5);
+        let y = camera.translation.y + half_height + rng.gen_range(0.0..half_height * 0.5);
+        let velocity = spec.velocity + Vec2::new(rng.gen_range(-spec.drift..=spec.drift), 0.);
+        commands.spawn((
+            WeatherParticle { velocity },
+            SpriteBundle {
+                sprite: Sprite {
+                    color: spec.color,
+                    custom_size: Some(spec.size),
+                    ..default()
+                },
+                transform: Transform::from_translation(Vec3::new(x, y, 40.)),
+                ..default()
+            },
+        ));
+    }
+}
+
+pub fn update_weather_particles(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut query: Query<(Entity, &WeatherParticle, &mut Transform), Without<BigBrother>>,
+    query_camera: Query<(&Transform, &OrthographicProjection), With<BigBrother>>,
+    windows: Query<&Window, With<PrimaryWindow>>,
+) {
+    let (Ok((camera, projection)), Ok(window)) = (query_camera.get_single(), windows.get_single())
+    else {
+        return;
+    };
+    let half_width = window.width() * projection.scale / 2.0;
+    let half_height = window.height() * projection.scale / 2.0;
+    let delta = time.delta_seconds();
+    for (entity, particle, mut transform) in query.iter_mut() {
+        transform.translation.x += particle.velocity.x * delta;
+        transform.translation.y += particle.velocity.y * delta;
+        // gone once it leaves the screen, or the camera jumped away from it
+        let offset = transform.translation.truncate() - camera.translation.truncate();
+        if offset.y < -half_height || offset.x.abs() > half_width * 3.0 {
+            commands.entity(entity).despawn_recursive();
+        }
+    }
+}
@@ -0,0 +1,193 @@
+use crate::world::{Content, TileType, WeatherType};
+
+// texture atlases, both are a single row of 64x64 tiles
+pub const TILES_ATLAS: &str = "tiles_robotic_lib.png";
+pub const CONTENTS_ATLAS: &str = "contents_robotic_lib.png";
+pub const ROBOT_SPRITE: &str = "robot_64x64.png";
+// four walking frames per row, rows face down, left, right and up
+pub const ROBOT_WALK_SHEET: &str = "robot_walk.png";
+pub const ROBOT_WALK_FRAMES: usize = 4;
+// one row of 32x32 icons
+pub const WEATHER_ICONS: &str = "weather_icons.png";
+
+// fog of war over undiscovered tiles, and the outline of tiles discovered this tick
+pub const FOG_ATLAS: &str = "fog.png";
+pub const FOG_INDEX: u32 = 0;
+pub const DISCOVERED_INDEX: u32 = 1;
+// a single white tile, tinted by the terrain overlays
+pub const OVERLAY_ATLAS: &str = "overlay.png";
+
+// index of a tile type in tiles_robotic_lib.png
+pub fn tile_texture_index(tile_type: &TileType) -> u32 {
+    match tile_type {
+        TileType::Grass => 0,
+        TileType::Sand => 1,
+        TileType::Snow => 2,
+        TileType::Mountain => 3,
+        TileType::Teleport(_) => 4,
+        TileType::Wall => 5,
+        TileType::Hill => 6,
+        TileType::Street => 7,
+        TileType::Lava => 8,
+        TileType::DeepWater => 9,
+        TileType::ShallowWater => 10,
+    }
+}
+
+pub fn tile_name(tile_type: &TileType) -> &'static str {
+    match tile_type {
+        TileType::Grass => "grass",
+        TileType::Sand => "sand",
+        TileType::Snow => "snow",
+        TileType::Mountain => "mountain",
+        TileType::Teleport(_) => "teleport",
+        TileType::Wall => "wall",
+        TileType::Hill => "hill",
+        TileType::Street => "street",
+        TileType::Lava => "lava",
+        TileType::DeepWater => "deep water",
+        TileType::ShallowWater => "shallow water",
+    }
+}
+
+// average color of a tile type, for the minimap
+pub fn tile_color(tile_type: &TileType) -> [u8; 4] {
+    match tile_type {
+        TileType::Grass => [86, 160, 52, 255],
+        TileType::Sand => [226, 200, 126, 255],
+        TileType::Snow => [236, 240, 248, 255],
+        TileType::Mountain => [122, 112, 104, 255],
+        TileType::Teleport(_) => [170, 84, 220, 255],
+        TileType::Wall => [64, 60, 58, 255],
+        TileType::Hill => [118, 150, 70, 255],
+        TileType::Street => [150, 150, 150, 255],
+        TileType::Lava => [232, 84, 24, 255],
+        TileType::DeepWater => [24, 54, 140, 255],
+        TileType::ShallowWater => [64, 134, 210, 255],
+    }
+}
+
+// index of a content in contents_robotic_lib.png, 0 is empty
+pub fn content_texture_index(content: &Content) -> u32 {
+    match content {
+        Content::None => 0,
+        Content::Water(_) => 1,
+        Content::Scarecrow => 2,
+        Content::Tree(_) => 3,
+        Content::Garbage(_) => 4,
+        Content::Bank(_) => 5,
+        Content::Crate(_) => 6,
+        Content::Fish(_) => 7,
+        Content::Market(_) => 8,
+        Content::Bush(_) => 9,
+        Content::Bin(_) => 10,
+        Content::Coin(_) => 11,
+        Content::Rock(_) => 12,
+        Content::JollyBlock(_) => 13,
+        Content::Fire => 14,
+        Content::Building => 15,
+    }
+}
+
+// name without the quantity, for labels and reports
+pub fn content_name(content: &Content) -> &'static str {
+    match content {
+        Content::None => "none",
+        Content::Water(_) => "water",
+        Content::Scarecrow => "scarecrow",
+        Content::Tree(_) => "tree",
+        Content::Garbage(_) => "garbage",
+        Content::Bank(_) => "bank",
+        Content::Crate(_) => "crate",
+        Content::Fish(_) => "fish",
+        Content::Market(_) => "market",
+        Content::Bush(_) => "bush",
+        Content::Bin(_) => "bin",
+        Content::Coin(_) => "coin",
+        Content::Rock(_) => "rock",
+        Content::JollyBlock(_) => "jolly block",
+        Content::Fire => "fire",
+        Content::Building => "building",
+    }
+}
+
+// amount held by a content, None for contents that don't hold any
+pub fn content_quantity(content: &Content) -> Option<usize> {
+    match content {
+        Content::Water(quantity)
+        | Content::Tree(quantity)
+        | Content::Garbage(quantity)
+        | Content::Fish(quantity)
+        | Content::Market(quantity)
+        | Content::Bush(quantity)
+        | Content::Coin(quantity)
+        | Content::Rock(quantity)
+        | Content::JollyBlock(quantity) => Some(*quantity),
+        _ => None,
+    }
+}
+
+// (stored, capacity) of the contents the robot fills up
+pub fn content_fill(content: &Content) -> Option<(usize, usize)> {
+    match content {
+        Content::Bank(range) | Content::Bin(range) | Content::Crate(range) => {
+            Some((range.start, range.end))
+        }
+        _ => None,
+    }
+}
+
+// index of a weather in weather_icons.png
+pub fn weather_icon_index(weather: &WeatherType) -> usize {
+    match weather {
+        WeatherType::Sunny => 0,
+        WeatherType::Rainy => 1,
+        WeatherType::Foggy => 2,
+        WeatherType::TropicalMonsoon => 3,
+        WeatherType::TrentinoSnow => 4,
+    }
+}
+
+pub fn weather_name(weather: &WeatherType) -> &'static str {
+    match weather {
+        WeatherType::Sunny => "sunny",
+        WeatherType::Rainy => "rainy",
+        WeatherType::Foggy => "foggy",
+        WeatherType::TropicalMonsoon => "tropical monsoon",
+        WeatherType::TrentinoSnow => "snow",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn teleports_use_the_teleport_texture() {
+        assert_eq!(tile_texture_index(&TileType::Teleport(true)), 4);
+        assert_eq!(tile_texture_index(&TileType::Teleport(false)), 4);
+    }
+
+    #[test]
+    fn other_tile_types_have_their_own_texture() {
+        let tile_types = [
+            TileType::DeepWater,
+            TileType::ShallowWater,
+            TileType::Sand,
+            TileType::Grass,
+            TileType::Street,
+            TileType::Hill,
+            TileType::Mountain,
+            TileType::Snow,
+            TileType::Lava,
+            TileType::Teleport(false),
+            TileType::Wall,
+        ];
+        let mut seen = Vec::new();
+        for tile_type in tile_types {
+            let index = tile_texture_index(&tile_type);
+            assert!(!seen.contains(&index), "{:?}", tile_type);
+            seen.push(index);
+        }
+    }
+}
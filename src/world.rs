@@ -0,0 +1,132 @@
+use std::ops::Range;
+
+use serde::{Deserialize, Serialize};
+
+// copies of the robotics_lib types the visualizer shows, so snapshots can be
+// saved and read back without the private crates. the wrapper converts the real
+// ones, see convert.rs
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug, Serialize, Deserialize)]
+pub enum TileType {
+    DeepWater,
+    ShallowWater,
+    Sand,
+    Grass,
+    Street,
+    Hill,
+    Mountain,
+    Snow,
+    Lava,
+    Teleport(bool),
+    Wall,
+}
+
+// whether the robot can walk on a tile type and the energy it costs, as in robotics_lib
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct TileProperties {
+    walk: bool,
+    cost: usize,
+}
+
+impl TileProperties {
+    pub fn walk(&self) -> bool {
+        self.walk
+    }
+
+    pub fn cost(&self) -> usize {
+        self.cost
+    }
+}
+
+impl TileType {
+    pub fn properties(&self) -> TileProperties {
+        let (walk, cost) = match self {
+            TileType::DeepWater => (false, 0),
+            TileType::ShallowWater => (true, 5),
+            TileType::Sand => (true, 3),
+            TileType::Grass => (true, 1),
+            TileType::Street => (true, 0),
+            TileType::Hill => (true, 5),
+            TileType::Mountain => (true, 10),
+            TileType::Snow => (true, 3),
+            TileType::Lava => (false, 0),
+            TileType::Teleport(_) => (true, 0),
+            TileType::Wall => (false, 0),
+        };
+        TileProperties { walk, cost }
+    }
+}
+
+#[derive(Clone, PartialEq, Eq, Hash, Debug, Serialize, Deserialize)]
+pub enum Content {
+    Rock(usize),
+    Tree(usize),
+    Garbage(usize),
+    Fire,
+    Coin(usize),
+    Bin(Range<usize>),
+    Crate(Range<usize>),
+    Bank(Range<usize>),
+    Water(usize),
+    Market(usize),
+    Fish(usize),
+    Building,
+    Bush(usize),
+    JollyBlock(usize),
+    Scarecrow,
+    None,
+}
+
+#[derive(Clone, PartialEq, Eq, Hash, Debug, Serialize, Deserialize)]
+pub struct Tile {
+    pub tile_type: TileType,
+    pub content: Content,
+    pub elevation: usize,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug, Serialize, Deserialize)]
+pub enum WeatherType {
+    Sunny,
+    Rainy,
+    Foggy,
+    TropicalMonsoon,
+    TrentinoSnow,
+}
+
+// what look_at_sky returned, the time is kept as robotics_lib formats it
+#[derive(Clone, PartialEq, Eq, Debug, Serialize, Deserialize)]
+pub struct EnvironmentalConditions {
+    time_of_day: String,
+    weather: WeatherType,
+}
+
+impl EnvironmentalConditions {
+    pub fn new(time_of_day: String, weather: WeatherType) -> Self {
+        Self {
+            time_of_day,
+            weather,
+        }
+    }
+
+    pub fn get_time_of_day_string(&self) -> String {
+        self.time_of_day.clone()
+    }
+
+    pub fn get_weather_condition(&self) -> WeatherType {
+        self.weather
+    }
+}
+
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
+pub enum Event {
+    Ready,
+    Terminated,
+    TimeChanged(EnvironmentalConditions),
+    DayChanged(EnvironmentalConditions),
+    EnergyRecharged(usize),
+    EnergyConsumed(usize),
+    Moved(Tile, (usize, usize)),
+    TileContentUpdated(Tile, (usize, usize)),
+    AddedToBackpack(Content, usize),
+    RemovedFromBackpack(Content, usize),
+}